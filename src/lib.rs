@@ -13,6 +13,16 @@ extern crate num_traits;
 extern crate log;
 extern crate arrayvec;
 extern crate env_logger;
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+use std::time::{Duration, Instant};
 
 use failure::Error;
 
@@ -22,3 +32,64 @@ pub mod utils;
 pub fn solve_problem(name: &str) -> Result<String, Error> {
     ::solutions::create_problem(name)?.solve()
 }
+
+/// Solve the problem, also giving how long it took to solve
+pub fn solve_problem_timed(name: &str) -> Result<(String, Duration), Error> {
+    let problem = ::solutions::create_problem(name)?;
+    let start = Instant::now();
+    let result = problem.solve()?;
+    Ok((result, start.elapsed()))
+}
+
+/// Solve every registered problem, giving the name of each problem with its result
+#[inline]
+pub fn solve_all() -> Vec<(&'static str, Result<String, Error>)> {
+    solve_problems(::solutions::problem_names())
+}
+
+/// Solve each of the named problems in order, giving the name of each problem with its result
+pub fn solve_problems<'a>(names: &[&'a str]) -> Vec<(&'a str, Result<String, Error>)> {
+    names.iter()
+        .map(|&name| (name, solve_problem(name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn timed_solve() {
+        let (result, elapsed) = solve_problem_timed("poker").unwrap();
+        assert_eq!(result, "376");
+        assert!(elapsed > Duration::from_secs(0));
+        assert!(solve_problem_timed("nonexistent").is_err());
+    }
+    fn check_results(results: Vec<(&str, Result<String, Error>)>) {
+        for (name, result) in results {
+            match result {
+                Ok(answer) => assert!(!answer.is_empty(), "Empty answer for {}", name),
+                Err(error) => panic!("Failed to solve {}: {}", name, error)
+            }
+        }
+    }
+    #[test]
+    fn solve_everything() {
+        let names = ::solutions::problem_names().iter()
+            .cloned()
+            .filter(|name| !::solutions::SLOW_PROBLEMS.contains(name))
+            .collect::<Vec<_>>();
+        // Every registered problem is either solved here or by `solve_all_problems`
+        assert_eq!(names.len() + ::solutions::SLOW_PROBLEMS.len(), ::solutions::problem_names().len());
+        let results = solve_problems(&names);
+        assert_eq!(results.len(), names.len());
+        assert!(results.iter().map(|&(name, _)| name).eq(names.iter().cloned()));
+        check_results(results);
+    }
+    #[test]
+    #[ignore] // The slow problems take minutes without optimizations
+    fn solve_all_problems() {
+        let results = solve_all();
+        assert_eq!(results.len(), ::solutions::problem_names().len());
+        check_results(results);
+    }
+}
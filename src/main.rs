@@ -4,27 +4,166 @@ extern crate clap;
 extern crate failure;
 extern crate env_logger;
 
+use std::fmt::Write;
 use std::process::exit;
+use std::thread;
+use std::time::Duration;
+
+use failure::Error;
 
 fn app() -> ::clap::App<'static, 'static> {
     clap_app!(euler =>
         (version: crate_version!())
         (author: crate_authors!())
         (about: crate_description!())
-        (@arg problem: +required "The name of the problem to solve")
+        (@arg problem: +multiple required_unless[range list all]
+            "The names of the problems to solve, which are solved in parallel")
+        (@arg range: --range +takes_value number_of_values(2) value_names(&["START", "END"])
+            conflicts_with[problem] "Solve every problem numbered between START and END (inclusive)")
+        (@arg list: --list conflicts_with[problem range] "List the names of every available problem")
+        (@arg all: --all conflicts_with[problem range list] "Solve every available problem")
+        (@arg time: --time conflicts_with[list all] "Print how long each problem took to solve")
+        (@arg format: --format +takes_value possible_values(&["text", "json"]) default_value("text")
+            "The format to print each result in (ignored by --list and --all)")
     )
 }
 
-fn main() {
-    ::env_logger::init();
-    let matches = app().get_matches();
-    let name = matches.value_of("problem").unwrap();
-    match ::euler::solve_problem(name) {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    /// A single json object per problem, always including the elapsed time
+    Json
+}
+
+/// Write the text as a json string, quoting it and escaping any special characters
+fn write_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{0}'...'\u{1F}' => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            _ => out.push(c)
+        }
+    }
+    out.push('"');
+}
+
+/// Render the result of solving the problem as a json object
+fn json_result(name: &str, result: &Result<(String, Duration), Error>) -> String {
+    let mut out = String::new();
+    out.push_str("{\"problem\":");
+    write_json_string(&mut out, name);
+    match *result {
+        Ok((ref answer, elapsed)) => {
+            out.push_str(",\"result\":");
+            write_json_string(&mut out, answer);
+            write!(out, ",\"elapsed_ms\":{:.3}", elapsed.as_secs_f64() * 1000.0).unwrap();
+        },
+        Err(ref error) => {
+            out.push_str(",\"error\":");
+            write_json_string(&mut out, &error.to_string());
+        }
+    }
+    out.push('}');
+    out
+}
+
+/// Solve the problem and print the result, returning whether it succeeded
+fn solve_and_print(name: &str, timed: bool, format: OutputFormat) -> bool {
+    if format == OutputFormat::Json {
+        let result = ::euler::solve_problem_timed(name);
+        println!("{}", json_result(name, &result));
+        return result.is_ok()
+    }
+    let result = if timed {
+        ::euler::solve_problem_timed(name).map(|(result, elapsed)| {
+            let millis = elapsed.as_secs_f64() * 1000.0;
+            format!("{} (in {:.2} ms)", result, millis)
+        })
+    } else {
+        ::euler::solve_problem(name)
+    };
+    match result {
         Ok(result) => {
-            println!("Solved {}: {}", name, result)
+            println!("Solved {}: {}", name, result);
+            true
         },
         Err(error) => {
             eprintln!("Failed to solve {}: {}", name, error);
+            false
+        }
+    }
+}
+
+fn main() {
+    ::env_logger::init();
+    let matches = app().get_matches();
+    let timed = matches.is_present("time");
+    let format = match matches.value_of("format").unwrap() {
+        "json" => OutputFormat::Json,
+        _ => OutputFormat::Text
+    };
+    if matches.is_present("list") {
+        for name in ::euler::solutions::problem_names() {
+            println!("{}", name);
+        }
+    } else if matches.is_present("all") {
+        let results = ::euler::solve_all();
+        let mut failures = 0;
+        for &(name, ref result) in &results {
+            match *result {
+                Ok(ref answer) => println!("Solved {}: {}", name, answer),
+                Err(ref error) => {
+                    eprintln!("Failed to solve {}: {}", name, error);
+                    failures += 1;
+                }
+            }
+        }
+        if failures > 0 {
+            eprintln!("Failed to solve {} of {} problems", failures, results.len());
+            exit(1)
+        }
+    } else if matches.is_present("range") {
+        let range = values_t!(matches, "range", u32).unwrap_or_else(|e| e.exit());
+        let names = ::euler::solutions::problems_in_range(range[0], range[1]);
+        if names.is_empty() {
+            eprintln!("No problems between {} and {}", range[0], range[1]);
+            exit(1)
+        }
+        let failures = names.iter().filter(|name| !solve_and_print(name, timed, format)).count();
+        if failures > 0 {
+            eprintln!("Failed to solve {} of {} problems", failures, names.len());
+            exit(1)
+        }
+    } else {
+        let names = matches.values_of("problem").unwrap()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        if format == OutputFormat::Text {
+            for name in &names {
+                if !::euler::solutions::problem_names().contains(&name.as_str()) {
+                    eprintln!("Unknown problem: {}", name);
+                    eprintln!("Run with --list to see the available problems");
+                    exit(1)
+                }
+            }
+        }
+        // Each result is printed by its own thread as soon as it's solved
+        let handles = names.iter().cloned().map(|name| {
+            thread::spawn(move || solve_and_print(&name, timed, format))
+        }).collect::<Vec<_>>();
+        let failures = handles.into_iter()
+            .map(|handle| handle.join().unwrap_or(false))
+            .filter(|&succeeded| !succeeded)
+            .count();
+        if failures > 0 {
+            if names.len() > 1 {
+                eprintln!("Failed to solve {} of {} problems", failures, names.len());
+            }
             exit(1)
         }
     }
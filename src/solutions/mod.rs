@@ -6,7 +6,7 @@ mod lychrel_numbers;
 mod powerful_digit_sum;
 mod square_root_convergents;
 mod spiral_primes;
-mod xor_decryption;
+pub mod xor_decryption;
 mod convergents_of_e;
 
 pub trait EulerSolution: Sized + ::std::fmt::Display {
@@ -44,38 +44,174 @@ impl<T: EulerSolution> EulerResult for T {
 }
 pub struct EulerProblem {
     name: &'static str,
+    number: Option<u32>,
+    difficulty: Option<u8>,
+    expected_answer: Option<&'static str>,
     func: Box<Fn() -> Result<String, Error> + Send + Sync + 'static>
 }
 impl EulerProblem {
     #[inline]
     pub fn new<R>(name: &'static str, func: fn() -> R) -> Self where  R: EulerResult + 'static {
+        EulerProblem::new_with_meta(name, func, None, None)
+    }
+    /// Create a problem with its Project Euler number and difficulty rating (as a percentage)
+    #[inline]
+    pub fn new_with_meta<R>(
+        name: &'static str, func: fn() -> R,
+        number: Option<u32>, difficulty: Option<u8>
+    ) -> Self where R: EulerResult + 'static {
         let func = Box::new(move || func().into_result());
-        EulerProblem { name, func }
+        EulerProblem { name, number, difficulty, expected_answer: None, func }
+    }
+    /// Give the problem a known correct answer, so that it can be verified
+    #[inline]
+    pub fn with_expected_answer(mut self, answer: &'static str) -> Self {
+        self.expected_answer = Some(answer);
+        self
+    }
+    /// The problem's number on Project Euler, if known
+    #[inline]
+    pub fn number(&self) -> Option<u32> {
+        self.number
+    }
+    /// The difficulty rating of the problem as a percentage, if known
+    #[inline]
+    pub fn difficulty(&self) -> Option<u8> {
+        self.difficulty
+    }
+    /// The known correct answer to the problem, if any
+    #[inline]
+    pub fn expected_answer(&self) -> Option<&'static str> {
+        self.expected_answer
     }
     #[inline]
     pub fn solve(&self) -> Result<String, Error> {
         (self.func)()
     }
+    /// Solve the problem and check the result against the expected answer
+    pub fn verify(&self) -> Result<bool, Error> {
+        let expected = match self.expected_answer {
+            Some(expected) => expected,
+            None => bail!("No expected answer for {}", self.name)
+        };
+        let result = self.solve()?;
+        if result != expected {
+            warn!("Expected {} for {}, but got {}", expected, self.name, result);
+        }
+        Ok(result == expected)
+    }
 }
 
-
+/// Solve the named problem and check the result against its known answer,
+/// failing if the problem doesn't have one.
+pub fn verify(name: &str) -> Result<bool, Error> {
+    create_problem(name)?.verify()
+}
 
 macro_rules! euler_problems {
-    ($target:ident, { $($problem:ident),* }) => {
-        Ok(match $target {
-            $ ( stringify!($problem) => EulerProblem::new(stringify!($problem), self::$problem::solve), ) *
-            _ => return Err(format_err!("Unknown problem: {}", $target))
-        })
+    ($($number:expr => $problem:ident (difficulty $difficulty:expr, answer $answer:expr)),*) => {
+        /// The number and name of every registered problem, in order of their number
+        pub const PROBLEMS: &[(u32, &str)] = &[$(($number, stringify!($problem))),*];
+        const PROBLEM_NAMES: &[&str] = &[$(stringify!($problem)),*];
+        /// The names of every registered problem, in order of their number
+        #[inline]
+        pub fn problem_names() -> &'static [&'static str] {
+            PROBLEM_NAMES
+        }
+        pub fn create_problem(name: &str) -> Result<EulerProblem, Error> {
+            Ok(match name {
+                $ ( stringify!($problem) => EulerProblem::new_with_meta(
+                    stringify!($problem), self::$problem::solve,
+                    Some($number), Some($difficulty)
+                ).with_expected_answer($answer), ) *
+                _ => return Err(format_err!("Unknown problem: {}", name))
+            })
+        }
     };
 }
-pub fn create_problem(name: &str) -> Result<EulerProblem, Error> {
-    euler_problems!(name, {
-        poker, lychrel_numbers,
-        prime_digit_replacements,
-        powerful_digit_sum,
-        square_root_convergents,
-        spiral_primes, xor_decryption,
-        convergents_of_e
-    })
+euler_problems! {
+    51 => prime_digit_replacements (difficulty 15, answer "121313"),
+    54 => poker (difficulty 10, answer "376"),
+    55 => lychrel_numbers (difficulty 5, answer "249"),
+    56 => powerful_digit_sum (difficulty 5, answer "972"),
+    57 => square_root_convergents (difficulty 5, answer "153"),
+    58 => spiral_primes (difficulty 5, answer "26241"),
+    59 => xor_decryption (difficulty 5, answer "107359"),
+    65 => convergents_of_e (difficulty 15, answer "272")
+}
+
+/// The problems that take minutes to solve without optimizations,
+/// which are only solved by tests when ignored tests are requested.
+#[cfg(test)]
+pub(crate) const SLOW_PROBLEMS: &[&str] = &["square_root_convergents"];
+
+/// The names of the registered problems whose numbers fall in the inclusive range
+pub fn problems_in_range(start: u32, end: u32) -> Vec<&'static str> {
+    PROBLEMS.iter()
+        .filter(|&&(number, _)| start <= number && number <= end)
+        .map(|&(_, name)| name)
+        .collect()
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn registry_ordered() {
+        assert!(PROBLEMS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+    #[test]
+    fn metadata() {
+        let poker = create_problem("poker").unwrap();
+        assert_eq!(poker.number(), Some(54));
+        assert_eq!(poker.difficulty(), Some(10));
+        for &(number, name) in PROBLEMS {
+            assert_eq!(create_problem(name).unwrap().number(), Some(number));
+        }
+        let unregistered = EulerProblem::new("unregistered", || 42u32);
+        assert_eq!(unregistered.number(), None);
+        assert_eq!(unregistered.difficulty(), None);
+        assert_eq!(unregistered.solve().unwrap(), "42");
+    }
+    #[test]
+    fn every_problem_resolves() {
+        for &(_, name) in PROBLEMS {
+            assert!(create_problem(name).is_ok(), "Unable to resolve {}", name);
+        }
+        for &name in SLOW_PROBLEMS {
+            assert!(create_problem(name).is_ok(), "Unknown slow problem {}", name);
+        }
+        assert!(create_problem("nonexistent").is_err());
+        let names = PROBLEMS.iter().map(|&(_, name)| name).collect::<Vec<_>>();
+        assert_eq!(problem_names(), &names[..]);
+    }
+    #[test]
+    fn verify_answers() {
+        assert_eq!(verify("poker").unwrap(), true);
+        assert_eq!(verify("spiral_primes").unwrap(), true);
+        let wrong = EulerProblem::new("wrong", || 41u32).with_expected_answer("42");
+        assert_eq!(wrong.expected_answer(), Some("42"));
+        assert_eq!(wrong.verify().unwrap(), false);
+        assert!(EulerProblem::new("unknown", || 42u32).verify().is_err());
+        assert!(verify("nonexistent").is_err());
+    }
+    #[test]
+    fn verify_all_answers() {
+        for &(_, name) in PROBLEMS {
+            if SLOW_PROBLEMS.contains(&name) { continue }
+            if create_problem(name).unwrap().expected_answer().is_some() {
+                assert_eq!(verify(name).unwrap(), true, "Wrong answer for {}", name);
+            }
+        }
+    }
+    #[test]
+    fn range() {
+        assert_eq!(
+            problems_in_range(50, 56),
+            vec!["prime_digit_replacements", "poker", "lychrel_numbers", "powerful_digit_sum"]
+        );
+        assert_eq!(problems_in_range(65, 65), vec!["convergents_of_e"]);
+        assert!(problems_in_range(60, 64).is_empty());
+        assert!(problems_in_range(58, 57).is_empty());
+    }
+}
@@ -1,10 +1,12 @@
 use std::ops::{Add, BitOr, BitAnd, BitOrAssign};
 use std::cmp::Ordering;
 use std::fmt::{self, Formatter, Display, Write};
+use std::str::FromStr;
+use std::collections::HashMap;
 
 use failure::Error;
-
-use super::EulerProblem;
+use itertools::Itertools;
+use rand::{Rng, SeedableRng, XorShiftRng};
 
 const POKER_HANDS_TEXT: &str = include_str!("poker.txt");
 
@@ -16,7 +18,7 @@ pub fn solve() -> Result<i32, Error> {
             cards.push(PokerCard::parse(card)?);
         }
         ensure!(cards.len() == 10, "Expected 10 cards: {:?}", line);
-        hands.push((PokerHand::new(&cards[..5]), PokerHand::new(&cards[5..])));
+        hands.push((PokerHand::try_new(&cards[..5])?, PokerHand::try_new(&cards[5..])?));
     }
     assert_eq!(hands.len(), 1000);
     let mut wins = 0;
@@ -34,6 +36,30 @@ pub fn solve() -> Result<i32, Error> {
     Ok(wins)
 }
 
+/// Estimate how often each kind of hand is dealt from a shuffled deck,
+/// by ranking the specified number of random hands.
+///
+/// The hands are grouped by their `PokerRank::category`, since counting each `PokerRank` separately
+/// would spread something like a pair over thirteen different values.
+/// The same seed always deals the same hands, so the results are reproducible.
+pub fn rank_distribution(samples: usize, seed: u32) -> HashMap<PokerRank, f64> {
+    assert!(samples > 0, "Need at least one sample");
+    let mut rng = XorShiftRng::from_seed([seed, 0x193a_6754, 0xa8a7_d469, 0x9783_0e05]);
+    let mut deck = (0..64).filter_map(PokerCard::from_id)
+        .filter(|card| card.value != PokerValue::One)
+        .collect::<Vec<_>>();
+    assert_eq!(deck.len(), 52);
+    let mut counts = HashMap::new();
+    for _ in 0..samples {
+        rng.shuffle(&mut deck);
+        let category = PokerHand::new(&deck[..5]).rank().category();
+        *counts.entry(category).or_insert(0usize) += 1;
+    }
+    counts.into_iter()
+        .map(|(category, count)| (category, count as f64 / samples as f64))
+        .collect()
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct PokerHand {
     cards: [PokerCard; 5]
@@ -46,6 +72,25 @@ impl PokerHand {
         result.cards.sort_by_key(|card| card.value);
         result
     }
+    /// Create a hand from five cards, checking that they're all distinct
+    pub fn try_new(cards: &[PokerCard]) -> Result<PokerHand, Error> {
+        ensure!(cards.len() == 5, "Expected 5 cards, but got {}", cards.len());
+        let mut seen = PokerSet::new();
+        for &card in cards {
+            ensure!(!seen.contains(card), "Duplicate card {} in hand", card);
+            seen.insert(card);
+        }
+        Ok(PokerHand::new(cards))
+    }
+    /// Find the best five card hand that can be made from six or seven cards,
+    /// by ranking every combination of them.
+    pub fn best_of(cards: &[PokerCard]) -> PokerHand {
+        assert!(cards.len() >= 5 && cards.len() <= 7, "Invalid number of cards: {}", cards.len());
+        cards.iter().cloned().combinations(5)
+            .map(|combination| PokerHand::new(&combination))
+            .max_by(|first, second| first.determine_winner(second))
+            .unwrap()
+    }
     #[inline]
     fn set(&self) -> PokerSet {
         let mut result = PokerSet::new();
@@ -56,8 +101,18 @@ impl PokerHand {
     }
     #[inline]
     fn determine_winner(&self, other: &PokerHand) -> Ordering {
-        self.rank().cmp(&other.rank())
-            .then_with(|| self.cards.iter().rev().cmp(other.cards.iter().rev()))
+        self.compare_detailed(other).0
+    }
+    /// Compare against the other hand, also giving the rank of both hands
+    /// so the caller can explain why one of them won.
+    pub fn compare_detailed(&self, other: &PokerHand) -> (Ordering, PokerRank, PokerRank) {
+        let (rank, other_rank) = (self.rank(), other.rank());
+        // Break ties by the card values, since the suits don't matter
+        let ordering = rank.cmp(&other_rank).then_with(|| {
+            self.cards.iter().rev().map(|card| card.value)
+                .cmp(other.cards.iter().rev().map(|card| card.value))
+        });
+        (ordering, rank, other_rank)
     }
     fn rank(&self) -> PokerRank {
         let set = self.set();
@@ -103,7 +158,7 @@ impl PokerHand {
             PokerRank::FullHouse { pair, three_of_a_kind }
         } else if first.suit.cards().contains_all(set) {
             PokerRank::Flush
-        } else if first.value.flush().map_or(false, |flush| flush.contains_all(set)) {
+        } else if pairs.is_empty() && first.value.flush().map_or(false, |flush| flush.contains_all(set)) {
             PokerRank::Straight(first.value)
         } else if let Some(three_of_a_kind) = three_of_a_kind {
             PokerRank::ThreeOfAKind(three_of_a_kind)
@@ -125,6 +180,20 @@ impl PokerHand {
         self.cards[4].value
     }
 }
+/// Hands are serialized as a list of their cards, and checked for duplicates when deserialized
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for PokerHand {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ::serde::Serialize::serialize(&self.cards, serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for PokerHand {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cards: Vec<PokerCard> = ::serde::Deserialize::deserialize(deserializer)?;
+        PokerHand::try_new(&cards).map_err(::serde::de::Error::custom)
+    }
+}
 impl Display for PokerHand {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_char('[')?;
@@ -140,7 +209,8 @@ impl Display for PokerHand {
     }
 }
 
-#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PokerRank {
     HighCard(PokerValue),
     OnePair(PokerValue),
@@ -156,6 +226,26 @@ pub enum PokerRank {
     StraightFlush(PokerValue),
     RoyalFlush
 }
+impl PokerRank {
+    /// The lowest rank in the same category, with every value replaced by `PokerValue::Two`.
+    ///
+    /// This lets ranks like a pair of kings and a pair of fives be counted together.
+    pub fn category(self) -> PokerRank {
+        use self::PokerValue::Two;
+        match self {
+            PokerRank::HighCard(_) => PokerRank::HighCard(Two),
+            PokerRank::OnePair(_) => PokerRank::OnePair(Two),
+            PokerRank::TwoPairs(_, _) => PokerRank::TwoPairs(Two, Two),
+            PokerRank::ThreeOfAKind(_) => PokerRank::ThreeOfAKind(Two),
+            PokerRank::Straight(_) => PokerRank::Straight(Two),
+            PokerRank::Flush => PokerRank::Flush,
+            PokerRank::FullHouse { .. } => PokerRank::FullHouse { three_of_a_kind: Two, pair: Two },
+            PokerRank::FourOfAKind(_) => PokerRank::FourOfAKind(Two),
+            PokerRank::StraightFlush(_) => PokerRank::StraightFlush(Two),
+            PokerRank::RoyalFlush => PokerRank::RoyalFlush,
+        }
+    }
+}
 
 #[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
 pub struct PokerCard {
@@ -173,11 +263,17 @@ impl PokerCard {
         let value = PokerValue::from_id(id % 16)?;
         Some(PokerCard { suit, value })
     }
+    /// Parse a card from its value followed by its suit, like `"QS"` or `"9H"`.
+    ///
+    /// Lowercase letters are also accepted, so `"qs"` parses the same as `"QS"`,
+    /// although the card will always be printed in uppercase.
+    /// A value of `1` is rejected, since `PokerValue::One` only exists for aces low.
     pub fn parse(text: &str) -> Result<PokerCard, Error> {
         if text.len() == 2 {
             let bytes = text.as_bytes();
-            if let Some(value) = PokerValue::parse(bytes[0] as char) {
-                if let Some(suit) = PokerSuit::parse(bytes[1] as char) {
+            let (value, suit) = (bytes[0].to_ascii_uppercase(), bytes[1].to_ascii_uppercase());
+            if let Some(value) = PokerValue::parse(value as char).filter(|&value| value != PokerValue::One) {
+                if let Some(suit) = PokerSuit::parse(suit as char) {
                     return Ok(PokerCard { suit, value })
                 }
             }
@@ -203,6 +299,39 @@ impl PokerCard {
             .map(|end| self.until(end))
     }
 }
+impl FromStr for PokerCard {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(text: &str) -> Result<PokerCard, Error> {
+        PokerCard::parse(text)
+    }
+}
+/// Cards are serialized as their two character string, like `"QS"`, instead of their id
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for PokerCard {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for PokerCard {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CardVisitor;
+        impl<'de> ::serde::de::Visitor<'de> for CardVisitor {
+            type Value = PokerCard;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("a two character poker card")
+            }
+
+            fn visit_str<E: ::serde::de::Error>(self, text: &str) -> Result<PokerCard, E> {
+                PokerCard::parse(text).map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_str(CardVisitor)
+    }
+}
 impl Display for PokerCard {
     #[inline]
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
@@ -212,6 +341,7 @@ impl Display for PokerCard {
 }
 
 #[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PokerSuit {
     Diamonds,
     Hearts,
@@ -233,7 +363,7 @@ impl PokerSuit {
             0 => PokerSuit::Diamonds,
             1 => PokerSuit::Hearts,
             2 => PokerSuit::Clubs,
-            3 => PokerSuit::Hearts,
+            3 => PokerSuit::Spades,
             _ => return None
         })
     }
@@ -259,11 +389,12 @@ impl PokerSuit {
     /// The set of all possible cards in this suit
     #[inline]
     pub fn cards(self) -> PokerSet {
-        PokerSet(((1u64 << 13) - 1) << (16 * self.id()))
+        PokerSet(((1u64 << 14) - 1) << (16 * self.id()))
     }
 }
 
-#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PokerValue {
     One,
     Two,
@@ -276,8 +407,8 @@ pub enum PokerValue {
     Nine,
     Ten,
     Jack,
-    King,
     Queen,
+    King,
     Ace,
 }
 impl PokerValue {
@@ -289,7 +420,7 @@ impl PokerValue {
             'Q' => PokerValue::Queen,
             'J' => PokerValue::Jack,
             'T' => PokerValue::Ten,
-            '0'...'9' => PokerValue::from_value((c as u8) - ('0' as u8)).unwrap(),
+            '0'...'9' => return PokerValue::from_value((c as u8) - ('0' as u8)),
             _ => return None
         })
     }
@@ -392,6 +523,10 @@ impl PokerSet {
         (self.0 & other.0) == other.0
     }
     #[inline]
+    pub fn contains(self, card: PokerCard) -> bool {
+        (self.0 & (1 << card.id())) != 0
+    }
+    #[inline]
     pub fn insert(&mut self, card: PokerCard) {
         self.0 |= 1 << card.id();
     }
@@ -422,3 +557,142 @@ impl BitOrAssign for PokerSet {
         self.0 |= rhs.0;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn hand(text: &str) -> PokerHand {
+        let cards = text.split_whitespace()
+            .map(|card| PokerCard::parse(card).unwrap())
+            .collect::<Vec<_>>();
+        PokerHand::new(&cards)
+    }
+    #[test]
+    fn detailed_comparison() {
+        let flush = hand("2H 4H 6H 8H TH");
+        let straight = hand("5C 6D 7H 8S 9C");
+        match flush.compare_detailed(&straight) {
+            (Ordering::Greater, PokerRank::Flush, PokerRank::Straight(_)) => {},
+            result => panic!("Unexpected comparison: {:?}", result)
+        }
+        let (ordering, _, _) = straight.compare_detailed(&flush);
+        assert_eq!(ordering, Ordering::Less);
+    }
+    #[test]
+    fn face_card_order() {
+        assert!(PokerValue::King > PokerValue::Queen);
+        assert!(PokerValue::Queen > PokerValue::Jack);
+        assert!(PokerValue::Ace > PokerValue::King);
+        for id in 0..14 {
+            let value = PokerValue::from_id(id).unwrap();
+            assert_eq!(value.id(), id);
+            assert_eq!(PokerValue::parse(value.print()), Some(value));
+        }
+        assert_eq!(PokerValue::parse('0'), None);
+        let kings = hand("KH KD 2C 3S 4H");
+        let queens = hand("QH QD 2D 3C 5H");
+        assert_eq!(kings.determine_winner(&queens), Ordering::Greater);
+        assert_eq!(queens.determine_winner(&kings), Ordering::Less);
+    }
+    #[test]
+    fn best_of_seven() {
+        let cards = "2C 3D KH 9H 4H 2H 7H".split_whitespace()
+            .map(|card| PokerCard::parse(card).unwrap())
+            .collect::<Vec<_>>();
+        let best = PokerHand::best_of(&cards);
+        assert_eq!(best.rank(), PokerRank::Flush);
+        assert_eq!(best, hand("KH 9H 4H 2H 7H"));
+        assert_eq!(PokerHand::new(&cards[..5]).rank(), PokerRank::HighCard(PokerValue::King));
+        // With six cards the best is a pair of twos
+        let best = PokerHand::best_of(&cards[..6]);
+        assert_eq!(best.rank(), PokerRank::OnePair(PokerValue::Two));
+        assert_eq!(best, hand("2C 2H KH 9H 4H"));
+    }
+    #[test]
+    fn duplicate_cards() {
+        let parse = |text: &str| text.split_whitespace()
+            .map(|card| PokerCard::parse(card).unwrap())
+            .collect::<Vec<_>>();
+        let error = PokerHand::try_new(&parse("AS 2D AS 4C 5H")).unwrap_err();
+        assert!(error.to_string().contains("AS"), "Unexpected error: {}", error);
+        assert!(PokerHand::try_new(&parse("AS 2D 3S 4C")).is_err());
+        assert_eq!(PokerHand::try_new(&parse("AS 2D AD 4C 5H")).unwrap(), hand("AS 2D AD 4C 5H"));
+    }
+    #[test]
+    fn suit_id_round_trip() {
+        for &suit in &PokerSuit::ALL {
+            assert_eq!(PokerSuit::from_id(suit.id()), Some(suit));
+        }
+        assert_eq!(PokerSuit::from_id(4), None);
+        let card = PokerCard::parse("QS").unwrap();
+        assert_eq!(PokerCard::from_id(card.id()), Some(card));
+    }
+    #[test]
+    fn card_round_trip() {
+        let mut count = 0;
+        for &suit in &PokerSuit::ALL {
+            for id in PokerValue::Two.id()..=PokerValue::Ace.id() {
+                let card = PokerCard { suit, value: PokerValue::from_id(id).unwrap() };
+                assert_eq!(card.to_string().parse::<PokerCard>().unwrap(), card);
+                assert_eq!(card.to_string().to_lowercase().parse::<PokerCard>().unwrap(), card);
+                assert_eq!(PokerCard::from_id(card.id()), Some(card));
+                count += 1;
+            }
+        }
+        assert_eq!(count, 52);
+        assert_eq!("tD".parse::<PokerCard>().unwrap().to_string(), "TD");
+        assert!("QX".parse::<PokerCard>().is_err());
+        assert!("Q".parse::<PokerCard>().is_err());
+        assert!("QSS".parse::<PokerCard>().is_err());
+        assert!("0H".parse::<PokerCard>().is_err());
+        assert!("1H".parse::<PokerCard>().is_err());
+    }
+    #[test]
+    fn rank_probabilities() {
+        let distribution = rank_distribution(20_000, 42);
+        let total: f64 = distribution.values().sum();
+        assert!((total - 1.0).abs() < 1e-9, "Invalid total: {}", total);
+        let mut kinds = distribution.iter().collect::<Vec<_>>();
+        kinds.sort_by(|first, second| second.1.partial_cmp(first.1).unwrap());
+        let (high_card, pair) = (PokerRank::HighCard(PokerValue::Two), PokerRank::OnePair(PokerValue::Two));
+        assert_eq!(*kinds[0].0, high_card);
+        assert_eq!(*kinds[1].0, pair);
+        // Roughly 50% and 42%, respectively
+        assert!((distribution[&high_card] - 0.501).abs() < 0.02);
+        assert!((distribution[&pair] - 0.423).abs() < 0.02);
+        assert!(distribution.keys().all(|&rank| rank.category() == rank));
+        assert_eq!(rank_distribution(1000, 7), rank_distribution(1000, 7));
+    }
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let original = hand("KH 9H 4H 2H 7H");
+        let json = ::serde_json::to_string(&original).unwrap();
+        assert_eq!(json, r#"["2H","4H","7H","9H","KH"]"#);
+        let deserialized: PokerHand = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, original);
+        let rank = ::serde_json::to_string(&original.rank()).unwrap();
+        assert_eq!(::serde_json::from_str::<PokerRank>(&rank).unwrap(), PokerRank::Flush);
+        let pair = PokerRank::OnePair(PokerValue::Queen);
+        let json = ::serde_json::to_string(&pair).unwrap();
+        assert_eq!(::serde_json::from_str::<PokerRank>(&json).unwrap(), pair);
+        assert!(::serde_json::from_str::<PokerHand>(r#"["2H","2H","7H","9H","KH"]"#).is_err());
+        assert!(::serde_json::from_str::<PokerCard>(r#""XX""#).is_err());
+        assert!(::serde_json::from_str::<PokerCard>(r#""0H""#).is_err());
+        assert!(::serde_json::from_str::<PokerHand>(r#"["0H","2H","7H","9H","KH"]"#).is_err());
+    }
+    #[test]
+    fn ranking_fixes() {
+        // A pair inside the range of a straight isn't a straight
+        assert_eq!(hand("6D 7C 5D 5H 3S").rank(), PokerRank::OnePair(PokerValue::Five));
+        assert_eq!(hand("3D 4C 5D 6H 7S").rank(), PokerRank::Straight(PokerValue::Three));
+        // Flushes can include an ace
+        assert_eq!(hand("AH 2H 6H 8H TH").rank(), PokerRank::Flush);
+        // Ties are broken by value, regardless of suit
+        let first = hand("5C 5S 2H 3D JC");
+        let second = hand("5D 5H 2S 3C JD");
+        assert_eq!(first.determine_winner(&second), Ordering::Equal);
+        let kicker = hand("6D 7C 5D 5H 3S");
+        assert_eq!(kicker.determine_winner(&first), Ordering::Less);
+    }
+}
@@ -40,7 +40,7 @@ fn digit_replacement_prime_families(bound_digits: usize, minimum_size: usize) ->
             let mut prime_family = Vec::with_capacity(minimum_size);
             for value in 0u8..10 {
                 for &index in replacement_indexes {
-                    digits.insert(index, value)
+                    digits.set(index, value)
                 }
                 if matrix.matrix[digits] && digits[0] != 0 {
                     prime_family.push(prime_digit_map[&digits])
@@ -119,7 +119,6 @@ pub fn solve() -> u32 {
 #[cfg(test)]
 mod test {
     use super::{corners, solve};
-    use solutions::EulerProblem;
     use itertools::Itertools;
     #[test]
     fn test_diagonals() {
@@ -7,7 +7,6 @@ use num::rational::{Ratio, BigRational};
 use num::integer::lcm;
 use num::BigInt;
 
-use solutions::EulerProblem;
 use utils::IntegerLogarithm;
 
 type SimplifiedFraction = BigRational;
@@ -43,6 +42,43 @@ impl Expansion {
             }
         }
     }
+    /// Simplify the expansion without recursion,
+    /// using an explicit stack so that deeply nested expansions can't overflow the real one.
+    fn simplify_iter(&self) -> SimplifiedFraction {
+        enum Task<'a> {
+            Simplify(&'a Expansion),
+            Add,
+            Divide
+        }
+        let mut tasks = vec![Task::Simplify(self)];
+        let mut values: Vec<SimplifiedFraction> = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Simplify(&Expansion::Integer(i)) => values.push(BigInt::from(i).into()),
+                Task::Simplify(&Expansion::Add(ref left, ref right)) => {
+                    // The left side is simplified first, so it's below the right on the stack
+                    tasks.push(Task::Add);
+                    tasks.push(Task::Simplify(right));
+                    tasks.push(Task::Simplify(left));
+                },
+                Task::Simplify(&Expansion::Fraction { ref numerator, ref denominator }) => {
+                    tasks.push(Task::Divide);
+                    tasks.push(Task::Simplify(denominator));
+                    tasks.push(Task::Simplify(numerator));
+                },
+                Task::Add | Task::Divide => {
+                    let right = values.pop().unwrap();
+                    let left = values.pop().unwrap();
+                    values.push(match task {
+                        Task::Add => left + right,
+                        _ => left / right
+                    });
+                }
+            }
+        }
+        debug_assert_eq!(values.len(), 1);
+        values.pop().unwrap()
+    }
     fn print(&self) -> String {
         let mut buffer = String::new();
         self.write_raw(&mut buffer, false).unwrap();
@@ -101,7 +137,7 @@ pub fn solve() -> i32 {
             debug!("Computed {} expansions", i + 1);
         }
         let expansion = square_root_expansion(i);
-        let frac = expansion.simplify();
+        let frac = expansion.simplify_iter();
         if numerator_has_more_digits(frac) {
             count += 1;
         }
@@ -119,12 +155,12 @@ fn square_root_expansion(count: usize) -> Expansion {
 }
 
 fn inner_square_root_expansion(count: usize) -> Expansion {
-    if count == 0 {
-        Expansion::Integer(2)
-    } else {
-        Expansion::Integer(2).add(
-            inner_square_root_expansion(count - 1).reciprocal())
+    // Build from the inside out, instead of recursing
+    let mut result = Expansion::Integer(2);
+    for _ in 0..count {
+        result = Expansion::Integer(2).add(result.reciprocal());
     }
+    result
 }
 
 #[cfg(test)]
@@ -157,6 +193,31 @@ mod test {
         }
     }
     #[test]
+    fn iterative_simplify() {
+        for index in 0..50 {
+            let expansion = square_root_expansion(index);
+            assert_eq!(expansion.simplify_iter(), expansion.simplify(), "Mismatch for {}", index);
+        }
+        // The convergents follow the recurrence h' = h + 2k, k' = h + k
+        let (mut numerator, mut denominator) = (BigInt::from(3), BigInt::from(2));
+        for _ in 0..999 {
+            let next = &numerator + &denominator * BigInt::from(2);
+            denominator = numerator + denominator;
+            numerator = next;
+        }
+        let expansion = square_root_expansion(999);
+        // Use a small stack, which recursing 1000 levels deep would overflow
+        let (expansion, frac) = ::std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let frac = expansion.simplify_iter();
+                (expansion, frac)
+            })
+            .unwrap().join().unwrap();
+        assert_eq!(frac, BigRational::new(numerator, denominator));
+        drop(expansion);
+    }
+    #[test]
     #[ignore] // too slow
     fn check_answer() {
         assert_eq!(
@@ -8,58 +8,213 @@ const COMMON_ENGLISH_WORDS: &[&str] = &[
     "be", "have", "good", "new", "do"
 ];
 
+/// The length of the keys used to encrypt the cipher text
+const KEY_LENGTH: usize = 3;
+/// The keys are guarenteed to be lowercase letters
+pub const LOWERCASE_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+/// The relative frequency of each letter in typical english text
+const ENGLISH_LETTER_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, // a-g
+    0.06094, 0.06966, 0.00153, 0.00772, 0.04025, 0.02406, 0.06749, // h-n
+    0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056, 0.02758, // o-u
+    0.00978, 0.02360, 0.00150, 0.01974, 0.00074 // v-z
+];
+/// Spaces are slightly more common than the letter 'e'
+const SPACE_FREQUENCY: f64 = 0.13;
+/// The relative frequency of punctuation and digits in typical english text
+const OTHER_FREQUENCY: f64 = 0.02;
+/// The penalty for each character that english text would never contain
+const NON_PRINTABLE_PENALTY: f64 = 1000.0;
+
 pub fn solve() -> u64 {
     let raw_data: &str = include_str!("cipher.txt");
     let mut bytes = Vec::new();
     for n in raw_data.split(',') {
         bytes.push(u8::from_str(n).unwrap())
     }
-    let mut best_match: Option<(usize, String)> = None;
-    for key in ::utils::product(&(b'a'..=b'z').collect::<Vec<_>>(), 3) {
-        if let Some(text) = decrypt_xor(&bytes, &key) {
-            let common_words = text.split_whitespace()
-                .filter(|t| COMMON_ENGLISH_WORDS.contains(t))
-                .count();
-            if !text.is_ascii() { continue } // Guarenteed to be ascii
-            if text.chars().any(|c| c.is_ascii_control()) { continue }
-            trace!("Decrypted {:?} with {} common words using {}", text, common_words, format_key(&key));
-            if common_words == 0 {
-                trace!("Zero common words for {:?} with key {}", text, format_key(&key));
-            } else if best_match.is_none() {
-                best_match = Some((common_words, text));
-            } else {
-                let best_match_words = best_match.as_ref().unwrap().0;
-                match common_words.cmp(&best_match_words) {
-                    Ordering::Less => {}, // ignore
-                    Ordering::Equal => {
-                        let best_match = &*best_match.as_ref().unwrap().1;
-                        warn!(
-                            "Equal number of common words ({}) for {:?} and {:?}",
-                            common_words, text,
-                            best_match
-                        );
-                    },
-                    Ordering::Greater => {
-                        {
-                            let best_match = &*best_match.as_ref().unwrap().1;
-                            debug!(
-                                "Increased number of common words from {:?} ({}) to {:?} ({})",
-                                best_match, best_match_words, text, common_words
-                            )
-                        }
-                        best_match = Some((common_words, text));
-                    },
+    let (key, best_match) = crack_xor(&bytes, LOWERCASE_ALPHABET, KEY_LENGTH, KeySearch::BruteForce).unwrap();
+    info!("Found best match {:?} using {}", best_match, format_key(&key));
+    best_match.chars().map(|s| s as u64).sum()
+}
+
+/// How to search for the key in `crack_xor`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeySearch {
+    /// Try every possible key, which is only feasible for short keys
+    BruteForce,
+    /// Pick each byte of the key independently,
+    /// based on the letter frequencies of the column it decrypts.
+    ///
+    /// This works for much longer keys, but needs enough cipher text
+    /// for the columns to look like english.
+    FrequencyAnalysis
+}
+
+/// Crack cipher text that was encrypted with a key of the specified length,
+/// giving the most likely key and plain text.
+///
+/// Only keys made from the bytes of `key_alphabet` are searched,
+/// which is `LOWERCASE_ALPHABET` for the original problem.
+pub fn crack_xor(bytes: &[u8], key_alphabet: &[u8], key_len: usize, search: KeySearch) -> Option<(Vec<u8>, String)> {
+    assert!(key_len > 0, "Invalid key length");
+    match search {
+        KeySearch::BruteForce => crack_single_xor(bytes, key_alphabet, key_len),
+        KeySearch::FrequencyAnalysis => crack_xor_columns(bytes, key_alphabet, key_len),
+    }
+}
+
+/// Brute force every key of the specified length made from the alphabet,
+/// giving the key and plain text whose letter frequencies are closest to english.
+///
+/// Ties are broken by the number of common english words.
+pub fn crack_single_xor(bytes: &[u8], key_alphabet: &[u8], key_len: usize) -> Option<(Vec<u8>, String)> {
+    let mut best_match: Option<(f64, usize, Vec<u8>, String)> = None;
+    for key in ::utils::product_iter(key_alphabet, key_len) {
+        if let Some(text) = decrypt_xor(bytes, &key) {
+            let score = frequency_score(&text);
+            let common_words = english_score(&text);
+            trace!(
+                "Decrypted {:?} with score {} and {} common words using {}",
+                text, score, common_words, format_key(&key)
+            );
+            let ordering = match best_match {
+                Some((best_score, best_words, _, _)) => {
+                    // Lower scores are better, but more common words are better
+                    best_score.partial_cmp(&score).unwrap()
+                        .then(common_words.cmp(&best_words))
+                },
+                None => Ordering::Greater
+            };
+            match ordering {
+                Ordering::Less => {}, // ignore
+                Ordering::Equal => {
+                    let best_match = &*best_match.as_ref().unwrap().3;
+                    warn!(
+                        "Equal score ({}) and common words ({}) for {:?} and {:?}",
+                        score, common_words, text, best_match
+                    );
+                },
+                Ordering::Greater => {
+                    if let Some((best_score, _, _, ref best_match)) = best_match {
+                        debug!(
+                            "Improved score from {:?} ({}) to {:?} ({})",
+                            best_match, best_score, text, score
+                        )
+                    }
+                    best_match = Some((score, common_words, key, text));
+                },
+            }
+        }
+    }
+    best_match.map(|(score, common_words, key, text)| {
+        debug!("Best match has score {} with {} common words", score, common_words);
+        (key, text)
+    })
+}
+
+/// Determine each byte of the key separately,
+/// choosing the one that makes its column of plain text look the most like english.
+fn crack_xor_columns(bytes: &[u8], key_alphabet: &[u8], key_len: usize) -> Option<(Vec<u8>, String)> {
+    let mut key = Vec::with_capacity(key_len);
+    for column in 0..key_len {
+        let mut best_match: Option<(f64, u8)> = None;
+        for &key_byte in key_alphabet {
+            if let Some(score) = column_frequency_score(bytes.iter().skip(column).step_by(key_len), key_byte) {
+                if best_match.map_or(true, |(best_score, _)| score > best_score) {
+                    best_match = Some((score, key_byte));
                 }
             }
         }
+        let (score, key_byte) = best_match?;
+        trace!("Best key byte for column {} is {:?} with score {}", column, key_byte as char, score);
+        key.push(key_byte);
     }
-    let (common_words, best_match) = best_match.unwrap();
-    info!("Found best match {:?} with {} common words", best_match, common_words);
-    best_match.chars().map(|s| s as u64).sum()
+    let text = decrypt_xor(bytes, &key)?;
+    Some((key, text))
+}
+
+/// Sum the english frequencies of the characters decrypted using the key byte,
+/// or `None` if any of them aren't printable.
+fn column_frequency_score<'a, I: Iterator<Item=&'a u8>>(column: I, key_byte: u8) -> Option<f64> {
+    let mut score = 0.0;
+    for &b in column {
+        let c = b ^ key_byte;
+        score += match c {
+            b'a'...b'z' => ENGLISH_LETTER_FREQUENCIES[(c - b'a') as usize],
+            b'A'...b'Z' => ENGLISH_LETTER_FREQUENCIES[(c - b'A') as usize],
+            b' ' => SPACE_FREQUENCY,
+            b'\n' | 0x21...0x7E => 0.0,
+            _ => return None
+        };
+    }
+    Some(score)
+}
+
+/// Score how much the text differs from english,
+/// using the chi-squared distance of its character frequencies from typical english text.
+///
+/// Lower scores are more likely to be english,
+/// and each non-printable character adds a large penalty to the score.
+/// Text without any letters can't be english, so it scores infinity.
+pub fn frequency_score(text: &str) -> f64 {
+    // The counts of each letter, followed by spaces and any other printable characters
+    let mut counts = [0usize; 28];
+    let mut total = 0;
+    let mut penalty = 0.0;
+    for c in text.chars() {
+        let index = match c {
+            'a'...'z' => (c as u8 - b'a') as usize,
+            'A'...'Z' => (c as u8 - b'A') as usize,
+            ' ' => 26,
+            '\n' | '!'...'~' => 27,
+            _ => {
+                penalty += NON_PRINTABLE_PENALTY;
+                continue
+            }
+        };
+        counts[index] += 1;
+        total += 1;
+    }
+    if counts[..26].iter().all(|&count| count == 0) {
+        return ::std::f64::INFINITY
+    }
+    let letter_frequency = 1.0 - SPACE_FREQUENCY - OTHER_FREQUENCY;
+    let mut chi_squared = 0.0;
+    for (index, &count) in counts.iter().enumerate() {
+        let frequency = match index {
+            26 => SPACE_FREQUENCY,
+            27 => OTHER_FREQUENCY,
+            _ => ENGLISH_LETTER_FREQUENCIES[index] * letter_frequency
+        };
+        let expected = frequency * total as f64;
+        let difference = count as f64 - expected;
+        chi_squared += difference * difference / expected;
+    }
+    chi_squared + penalty
+}
+
+/// Score how likely the text is to be english, by counting the common english words it contains.
+///
+/// Higher scores are more likely to be english, and text without any common words scores zero.
+///
+/// ```
+/// use euler::solutions::xor_decryption::{encrypt_xor, decrypt_xor, english_score};
+/// let message = "she is good and he has time to be new";
+/// let encrypted = encrypt_xor(message, b"key");
+/// assert_ne!(&encrypted[..], message.as_bytes());
+/// let decrypted = decrypt_xor(&encrypted, b"key").unwrap();
+/// assert_eq!(decrypted, message);
+/// assert_eq!(english_score(&decrypted), 8);
+/// assert_eq!(english_score("xyzzy plugh"), 0);
+/// ```
+pub fn english_score(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|t| COMMON_ENGLISH_WORDS.contains(t))
+        .count()
 }
 
-#[cfg_attr(not(test), allow(unused))]
-fn encrypt_xor(text: &str, key: &[u8]) -> Vec<u8> {
+/// Encrypt the ascii text by xoring it with the key, repeating the key as needed.
+pub fn encrypt_xor(text: &str, key: &[u8]) -> Vec<u8> {
     assert!(text.is_ascii());
     let mut result = Vec::with_capacity(text.len());
     for (&b, &key_byte) in text.as_bytes().iter().zip(key.iter().cycle()) {
@@ -67,7 +222,9 @@ fn encrypt_xor(text: &str, key: &[u8]) -> Vec<u8> {
     }
     result
 }
-fn decrypt_xor(bytes: &[u8], key: &[u8]) -> Option<String> {
+/// Decrypt the bytes by xoring them with the repeating key,
+/// or `None` if the result isn't valid UTF8.
+pub fn decrypt_xor(bytes: &[u8], key: &[u8]) -> Option<String> {
     let mut result = Vec::with_capacity(bytes.len());
     for (&b, &key_byte) in bytes.iter().zip(key.iter().cycle()) {
         result.push(b ^ key_byte);
@@ -88,7 +245,7 @@ fn format_key(key: &[u8]) -> String {
 
 #[cfg(test)]
 mod test {
-    use super::{decrypt_xor, encrypt_xor};
+    use super::*;
     const TEST_KEYS: &[&[u8]] = &[
         b"acd",
         b"zrt",
@@ -111,4 +268,59 @@ mod test {
             }
         }
     }
+    #[test]
+    fn crack_uppercase_key() {
+        let text = "She has time to be good and he is new, so do you want to have tea too?";
+        let encrypted = encrypt_xor(text, b"XKC");
+        let uppercase = (b'A'..=b'Z').collect::<Vec<u8>>();
+        let (key, decrypted) = crack_single_xor(&encrypted, &uppercase, 3).unwrap();
+        assert_eq!(key, b"XKC");
+        assert_eq!(decrypted, text);
+        let encrypted = encrypt_xor(text, b"1984");
+        let (key, decrypted) = crack_xor(&encrypted, b"0123456789", 4, KeySearch::BruteForce).unwrap();
+        assert_eq!(key, b"1984");
+        assert_eq!(decrypted, text);
+    }
+    #[test]
+    fn crack_longer_keys() {
+        let text = "She has time to be good and he is new, so do you want to have tea too? \
+            The person said to be good is there, and he will do what he has to do when the time comes. \
+            Everybody wants to know what is new, but the news is always the same old story.";
+        // Keep the text short when brute forcing, since there are almost half a million keys
+        let short_text = "she has time to be good and he is new, so do you want to have tea too?";
+        let (key, decrypted) = crack_xor(&encrypt_xor(short_text, b"rust"), LOWERCASE_ALPHABET, 4, KeySearch::BruteForce).unwrap();
+        assert_eq!(key, b"rust");
+        assert_eq!(decrypted, short_text);
+        let (key, decrypted) = crack_xor(&encrypt_xor(text, b"rust"), LOWERCASE_ALPHABET, 4, KeySearch::FrequencyAnalysis).unwrap();
+        assert_eq!(key, b"rust");
+        assert_eq!(decrypted, text);
+        // Frequency analysis is fast enough for much longer keys
+        let encrypted = encrypt_xor(text, b"pianoplayer");
+        let (key, decrypted) = crack_xor(&encrypted, LOWERCASE_ALPHABET, 11, KeySearch::FrequencyAnalysis).unwrap();
+        assert_eq!(key, b"pianoplayer");
+        assert_eq!(decrypted, text);
+    }
+    #[test]
+    fn frequency_scoring() {
+        let text = "The rain in spain falls gently on the plain, so do you want to have tea too?";
+        let encrypted = encrypt_xor(text, b"abc");
+        let correct = frequency_score(&decrypt_xor(&encrypted, b"abc").unwrap());
+        for &key in &[b"abd", b"bbc", b"xyz", b"ABC", b"aac"] {
+            let wrong = decrypt_xor(&encrypted, key).unwrap();
+            let score = frequency_score(&wrong);
+            assert!(correct < score, "{:?} scored {} but the plain text scored {}", wrong, score, correct);
+        }
+        let penalty = frequency_score("the plain\u{1}\u{2}\u{3}") - frequency_score("the plain");
+        assert!(penalty >= 3.0 * NON_PRINTABLE_PENALTY, "Penalty of {} is too small", penalty);
+        assert!(frequency_score("the plain text") < frequency_score("zqx jvkw qzx"));
+        assert_eq!(frequency_score(""), ::std::f64::INFINITY);
+        assert_eq!(frequency_score("  1, 2, 3!"), ::std::f64::INFINITY);
+        assert!(frequency_score("zqx jvkw qzx").is_finite());
+    }
+    #[test]
+    fn score_common_words() {
+        assert_eq!(english_score("she has time to be good"), 5);
+        assert_eq!(english_score("She has"), 1);
+        assert_eq!(english_score(""), 0);
+    }
 }
\ No newline at end of file
@@ -1,5 +1,10 @@
 use num::rational::Ratio;
-use num::{Zero, BigRational, BigInt, ToPrimitive};
+use num::{Zero, One, BigRational, BigInt, ToPrimitive};
+use std::iter;
+use std::mem;
+use num::bigint::ToBigUint;
+
+use utils::{big_digit_sum, isqrt};
 
 pub struct ContinuedFraction {
     first_digit: u32,
@@ -10,6 +15,24 @@ impl ContinuedFraction {
     pub fn new(first: u32, remaining: Vec<u32>) -> ContinuedFraction {
         ContinuedFraction { first_digit: first, remaining }
     }
+    /// Expand the rational number into its finite continued fraction,
+    /// using the quotients from the Euclidean algorithm.
+    ///
+    /// Panics if any of the terms don't fit in a `u32`.
+    pub fn from_ratio(ratio: Ratio<u64>) -> ContinuedFraction {
+        let (mut numerator, mut denominator) = (*ratio.numer(), *ratio.denom());
+        let mut terms = Vec::new();
+        while denominator != 0 {
+            let quotient = numerator / denominator;
+            assert!(quotient <= (u32::max_value() as u64), "Term {} of {} is too large", quotient, ratio);
+            terms.push(quotient as u32);
+            let remainder = numerator % denominator;
+            numerator = denominator;
+            denominator = remainder;
+        }
+        let first = terms.remove(0);
+        ContinuedFraction::new(first, terms)
+    }
     pub fn eval_convergent(&self, index: usize) -> Ratio<u64> {
         // TODO: Avoid using eval_big_convergent
         let ratio = self.eval_big_convergent(index);
@@ -33,6 +56,34 @@ impl ContinuedFraction {
         BigRational::from_integer(self.first_digit.into())
             + val.map_or(BigRational::zero(), |v| v.recip())
     }
+    /// Iterate over every convergent in order, giving the same results as `eval_big_convergent`.
+    ///
+    /// Each convergent comes from the previous two using `h_n = a_n*h_{n-1} + h_{n-2}`
+    /// (and likewise for the denominators), instead of recomputing the whole fraction.
+    pub fn convergents<'a>(&'a self) -> impl Iterator<Item=BigRational> + 'a {
+        let mut numerators = (BigInt::zero(), BigInt::one());
+        let mut denominators = (BigInt::one(), BigInt::zero());
+        iter::once(&self.first_digit).chain(self.remaining.iter()).map(move |&term| {
+            let term = BigInt::from(term);
+            let numerator = &term * &numerators.1 + &numerators.0;
+            let denominator = &term * &denominators.1 + &denominators.0;
+            numerators.0 = mem::replace(&mut numerators.1, numerator.clone());
+            denominators.0 = mem::replace(&mut denominators.1, denominator.clone());
+            // The convergents are always in lowest terms
+            BigRational::new_raw(numerator, denominator)
+        })
+    }
+    /// Approximate the convergent as a float,
+    /// folding the terms back from the tail without any big integers.
+    pub fn eval_f64(&self, index: usize) -> f64 {
+        assert!(index <= self.remaining.len());
+        let tail = self.remaining[..index].iter().rev()
+            .fold(None, |val: Option<f64>, &value| Some(match val {
+                Some(existing) => value as f64 + existing.recip(),
+                None => value as f64
+            }));
+        self.first_digit as f64 + tail.map_or(0.0, f64::recip)
+    }
     pub fn e(len: usize) -> ContinuedFraction {
         let mut remaining = Vec::new();
         remaining.push(1);
@@ -54,11 +105,44 @@ impl ContinuedFraction {
             remaining: vec![2; len]
         }
     }
+    /// The golden ratio, whose terms are all one
+    pub fn phi(len: usize) -> ContinuedFraction {
+        ContinuedFraction {
+            first_digit: 1,
+            remaining: vec![1; len]
+        }
+    }
+    /// Compute the first term and repeating period of the continued fraction of `sqrt(n)`,
+    /// or `None` if `n` is a perfect square and the square root is rational.
+    ///
+    /// The period always ends with twice the first term, which is how we know we're done.
+    pub fn sqrt(n: u64) -> Option<(u32, Vec<u32>)> {
+        assert!(n < (1 << 62), "Too large: {}", n);
+        let first = isqrt(n);
+        if first * first == n { return None }
+        let (mut m, mut d, mut a) = (0, 1, first);
+        let mut period = Vec::new();
+        while a != 2 * first {
+            m = d * a - m;
+            d = (n - m * m) / d;
+            a = (first + m) / d;
+            period.push(a as u32);
+        }
+        Some((first as u32, period))
+    }
+}
+
+/// Sum the digits in the numerator of the convergent of `e`,
+/// where the index counts from one like Project Euler does.
+pub fn e_convergent_numerator_digit_sum(index: usize) -> u64 {
+    assert!(index >= 1, "Invalid index: {}", index);
+    let convergent = ContinuedFraction::e(index - 1).eval_big_convergent(index - 1);
+    big_digit_sum(&convergent.numer().to_biguint().unwrap())
 }
 
 #[cfg(test)]
 mod test {
-    use utils::ContinuedFraction;
+    use utils::{ContinuedFraction, e_convergent_numerator_digit_sum};
     use num::rational::Ratio;
 
     #[test]
@@ -102,4 +186,86 @@ mod test {
             Ratio::new(17, 12)
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn convergents() {
+        let sqrt2 = ContinuedFraction::sqrt2(20);
+        let convergents = sqrt2.convergents().collect::<Vec<_>>();
+        assert_eq!(convergents.len(), 21);
+        for (index, convergent) in convergents.iter().take(10).enumerate() {
+            assert_eq!(*convergent, sqrt2.eval_big_convergent(index), "Convergent {}", index);
+        }
+        let e = ContinuedFraction::e(30);
+        for (index, convergent) in e.convergents().enumerate() {
+            assert_eq!(convergent, e.eval_big_convergent(index), "Convergent {}", index);
+        }
+    }
+
+    #[test]
+    fn eval_f64() {
+        let e = ContinuedFraction::e(20);
+        assert!((e.eval_f64(20) - ::std::f64::consts::E).abs() < 1e-9);
+        assert_eq!(e.eval_f64(0), 2.0);
+        assert_eq!(e.eval_f64(2), 8.0 / 3.0);
+        let sqrt2 = ContinuedFraction::sqrt2(30);
+        assert!((sqrt2.eval_f64(30) - ::std::f64::consts::SQRT_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn from_ratio() {
+        let fraction = ContinuedFraction::from_ratio(Ratio::new(415, 93));
+        assert_eq!(fraction.first_digit, 4);
+        assert_eq!(fraction.remaining, vec![2, 6, 7]);
+        let integer = ContinuedFraction::from_ratio(Ratio::from_integer(7));
+        assert_eq!(integer.first_digit, 7);
+        assert!(integer.remaining.is_empty());
+        let small = ContinuedFraction::from_ratio(Ratio::new(3, 8));
+        assert_eq!(small.first_digit, 0);
+        assert_eq!(small.remaining, vec![2, 1, 2]);
+        for &(numer, denom) in &[(415, 93), (7, 1), (3, 8), (1, 1000), (1457, 536), (987, 610)] {
+            let ratio = Ratio::new(numer, denom);
+            let fraction = ContinuedFraction::from_ratio(ratio);
+            assert_eq!(fraction.eval_convergent(fraction.remaining.len()), ratio);
+        }
+    }
+
+    #[test]
+    fn phi() {
+        let phi = ContinuedFraction::phi(40);
+        assert_eq!(phi.eval_convergent(0), Ratio::new(1, 1));
+        assert_eq!(phi.eval_convergent(4), Ratio::new(8, 5));
+        // The convergents are ratios of consecutive Fibonacci numbers
+        let (mut previous, mut current) = (0u64, 1u64);
+        for index in 0..41 {
+            let next = previous + current;
+            assert_eq!(phi.eval_convergent(index), Ratio::new(next, current));
+            previous = current;
+            current = next;
+        }
+    }
+
+    #[test]
+    fn sqrt() {
+        assert_eq!(ContinuedFraction::sqrt(23), Some((4, vec![1, 3, 1, 8])));
+        assert_eq!(ContinuedFraction::sqrt(2), Some((1, vec![2])));
+        assert_eq!(ContinuedFraction::sqrt(13), Some((3, vec![1, 1, 1, 1, 6])));
+        assert_eq!(ContinuedFraction::sqrt(16), None);
+        assert_eq!(ContinuedFraction::sqrt(1), None);
+        // Exactly four roots up to thirteen have an odd period
+        let odd_periods = (2..14)
+            .filter_map(ContinuedFraction::sqrt)
+            .filter(|&(_, ref period)| period.len() % 2 == 1)
+            .count();
+        assert_eq!(odd_periods, 4);
+        let (first, period) = ContinuedFraction::sqrt(2).unwrap();
+        assert_eq!(ContinuedFraction::new(first, period.repeat(4)).eval_convergent(3), Ratio::new(17, 12));
+    }
+
+    #[test]
+    fn e_numerator_digit_sum() {
+        assert_eq!(e_convergent_numerator_digit_sum(1), 2);
+        // The tenth convergent is 1457/536
+        assert_eq!(e_convergent_numerator_digit_sum(10), 17);
+        assert_eq!(e_convergent_numerator_digit_sum(100), 272);
+    }
+}
@@ -1,14 +1,17 @@
-use std::ops::{Add, AddAssign, Index};
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, Index, RangeInclusive};
 use std::str::FromStr;
-use std::fmt::{self, Debug, Formatter};
+use std::fmt::{self, Debug, Display, Formatter, Write};
 use std::hash::{Hash, Hasher};
+use std::cmp::Ordering;
+use std::{iter, slice};
 
 use fixedbitset::FixedBitSet;
 use ndarray::{NdIndex, IxDyn};
 use itertools::Itertools;
 use itertools::EitherOrBoth::*;
-use num::{BigInt, Integer, Zero, ToPrimitive, Signed};
+use num::{BigInt, BigUint, Integer, Zero, ToPrimitive, Signed};
 use num_traits::NumCast;
+use failure::Error;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Digits {
@@ -29,15 +32,40 @@ impl Digits {
         }
         Digits { values, len: digits.len() as u8 }
     }
-    pub fn from_value(mut num: u64) -> Digits {
-        if num == 0 {
-            return Digits { values: [0; 20], len: 1 }
-        }
+    #[inline]
+    pub fn from_value(num: u64) -> Digits {
+        Digits::from_value_radix(num, 10)
+    }
+    /// Split a `u128` into its decimal digits,
+    /// panicking if it needs more than twenty of them.
+    pub fn from_u128(mut num: u128) -> Digits {
+        let original = num;
         let mut result = Digits::new();
-        while num > 0 {
-            let digit = num % 10;
+        loop {
+            assert!(result.len < 20, "{} needs more than 20 digits", original);
+            result.values[result.len as usize] = (num % 10) as u8;
+            result.len += 1;
             num /= 10;
-            result.push(digit as u8);
+            if num == 0 { break }
+        }
+        result.reverse();
+        result
+    }
+    /// Split the value into its digits in the specified radix.
+    ///
+    /// Panics if the value needs more than twenty digits,
+    /// so large values in small bases may not fit.
+    pub fn from_value_radix(mut num: u64, radix: u8) -> Digits {
+        assert!(radix >= 2, "Invalid radix: {}", radix);
+        let original = num;
+        let radix = radix as u64;
+        let mut result = Digits::new();
+        loop {
+            assert!(result.len < 20, "{} needs more than 20 digits in base {}", original, radix);
+            result.values[result.len as usize] = (num % radix) as u8;
+            result.len += 1;
+            num /= radix;
+            if num == 0 { break }
         }
         result.reverse();
         result
@@ -71,11 +99,70 @@ impl Digits {
     pub fn len(&self) -> u8 {
         self.len
     }
+    /// Check if the digits are exactly a permutation of `1..=n`,
+    /// each appearing once with no zeros.
+    ///
+    /// Like `digit_counts`, this only supports decimal digits.
+    pub fn is_pandigital(&self, n: u8) -> bool {
+        assert!(n >= 1 && n <= 9, "Invalid pandigital size: {}", n);
+        self.len == n && self.digit_mask() == ((1 << (n + 1)) - 2)
+    }
+    /// Check if the digits are exactly a permutation of `0..=9`
+    #[inline]
+    pub fn is_pandigital_0_to_9(&self) -> bool {
+        self.len == 10 && self.digit_mask() == 0b11_1111_1111
+    }
+    /// Count how many times each digit appears.
+    ///
+    /// This only supports decimal digits,
+    /// and panics if there are larger ones from `from_value_radix`.
+    #[inline]
+    pub fn digit_counts(&self) -> [u8; 10] {
+        let mut counts = [0; 10];
+        for &digit in self.as_slice() {
+            assert!(digit < 10, "Can only count decimal digits: {:?}", self);
+            counts[digit as usize] += 1;
+        }
+        counts
+    }
+    /// A bitmask of which decimal digits are present
+    #[inline]
+    fn digit_mask(&self) -> u16 {
+        self.as_slice().iter().fold(0, |mask, &digit| {
+            assert!(digit < 10, "Expected decimal digits: {:?}", self);
+            mask | (1 << digit)
+        })
+    }
+    /// The sum of the digits
+    #[inline]
+    pub fn sum(&self) -> u32 {
+        self.as_slice().iter().map(|&digit| digit as u32).sum()
+    }
+    /// Convert to a `BigUint`, which never overflows like `value` can
+    #[inline]
+    pub fn to_biguint(&self) -> BigUint {
+        digits_to_biguint(self.as_slice())
+    }
+    /// The product of the digits, which is zero if any of them are zero
+    #[inline]
+    pub fn product(&self) -> u64 {
+        let mut result = 1u64;
+        for &digit in self.as_slice() {
+            if digit == 0 { return 0 }
+            result *= digit as u64;
+        }
+        result
+    }
+    #[inline]
     pub fn value(&self) -> u64 {
+        self.value_radix(10)
+    }
+    /// Combine the digits into a value, interpreting them in the specified radix
+    pub fn value_radix(&self, radix: u8) -> u64 {
         let mut result = 0u64;
         for &digit in self.as_slice() {
-            debug_assert!(digit < 10, "Invalid digit: {}", digit);
-            result *= 10;
+            debug_assert!(digit < radix, "Invalid digit for base {}: {}", radix, digit);
+            result *= radix as u64;
             result += digit as u64;
         }
         result
@@ -96,11 +183,25 @@ impl Digits {
         self.values[self.len as usize] = digit;
         self.len += 1;
     }
+    /// Overwrite the digit at the specified index, without changing the length
     #[inline]
-    pub fn insert(&mut self, index: usize, digit: u8) {
+    pub fn set(&mut self, index: usize, digit: u8) {
         assert!(digit < 10, "Invalid digit: {}", digit);
         self.as_mut_slice()[index] = digit;
     }
+    /// Insert the digit at the specified index, shifting all the digits after it to the right
+    #[inline]
+    pub fn insert(&mut self, index: usize, digit: u8) {
+        assert!(digit < 10, "Invalid digit: {}", digit);
+        assert!(self.len < 20, "Capacity overflow inserting {} into {:?}", digit, self);
+        let len = self.len as usize;
+        assert!(index <= len, "Index {} is out of bounds for {:?}", index, self);
+        for i in (index..len).rev() {
+            self.values[i + 1] = self.values[i];
+        }
+        self.values[index] = digit;
+        self.len += 1;
+    }
     #[inline]
     pub fn reversed(mut self) -> Digits {
         self.reverse();
@@ -110,11 +211,39 @@ impl Digits {
     pub fn reverse(&mut self) {
         self.as_mut_slice().reverse();
     }
+    /// Rotate the digits to the left by the specified amount, wrapping around.
+    ///
+    /// This can give leading zeros, which are kept as part of the digits.
+    #[inline]
+    pub fn rotate_left(&mut self, amount: usize) {
+        if self.len > 0 {
+            let amount = amount % (self.len as usize);
+            self.as_mut_slice().rotate_left(amount);
+        }
+    }
+    #[inline]
+    pub fn rotated_left(mut self, amount: usize) -> Digits {
+        self.rotate_left(amount);
+        self
+    }
+    /// Iterate over every rotation of the digits, starting with the original.
+    ///
+    /// There are always `len` rotations, even if some of them are the same,
+    /// and leading zeros from rotating are kept like in `rotate_left`.
+    #[inline]
+    pub fn rotations(&self) -> impl Iterator<Item=Digits> {
+        let digits = *self;
+        (0..(self.len as usize)).map(move |amount| digits.rotated_left(amount))
+    }
     #[inline]
     pub fn as_slice(&self) -> &[u8] {
         &self.values[..(self.len as usize)]
     }
     #[inline]
+    pub fn iter(&self) -> slice::Iter<u8> {
+        self.as_slice().iter()
+    }
+    #[inline]
     fn as_mut_slice(&mut self) -> &mut [u8] {
         &mut self.values[..(self.len as usize)]
     }
@@ -127,6 +256,11 @@ impl Digits {
         indexes
     }
 }
+/// Evaluate the digits using Horner's method
+fn digits_to_biguint(digits: &[u8]) -> BigUint {
+    let ten = BigUint::from(10u32);
+    digits.iter().fold(BigUint::zero(), |value, &digit| value * &ten + BigUint::from(digit))
+}
 #[inline]
 fn is_palindrome(digits: &[u8]) -> bool {
     let half = digits.len() / 2;
@@ -137,6 +271,65 @@ impl Debug for Digits {
         f.debug_list().entries(self.as_slice()).finish()
     }
 }
+/// Writes the digits consecutively, including any leading zeros.
+///
+/// Digits above nine (from a larger radix) are written as lowercase letters like hexadecimal,
+/// and empty digits don't print anything at all.
+impl Display for Digits {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_digits(self.as_slice(), f)
+    }
+}
+#[inline]
+fn write_digits(digits: &[u8], f: &mut Formatter) -> fmt::Result {
+    for &digit in digits {
+        let c = ::std::char::from_digit(digit as u32, 36)
+            .unwrap_or_else(|| panic!("Invalid digit: {}", digit));
+        f.write_char(c)?;
+    }
+    Ok(())
+}
+/// Parses a string of decimal digits, preserving any leading zeros.
+///
+/// Errors on an empty string, on non-digit characters, or if there are more than twenty digits.
+impl FromStr for Digits {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Digits, Error> {
+        ensure!(!text.is_empty(), "Empty digits");
+        ensure!(text.len() <= 20, "Too many digits: {:?}", text);
+        let mut result = Digits::new();
+        for c in text.chars() {
+            result.push(parse_digit(c)?);
+        }
+        Ok(result)
+    }
+}
+#[inline]
+fn parse_digit(c: char) -> Result<u8, Error> {
+    match c.to_digit(10) {
+        Some(digit) => Ok(digit as u8),
+        None => Err(format_err!("Invalid digit: {:?}", c))
+    }
+}
+/// Orders by the number of digits first, then compares the digits themselves.
+///
+/// This matches numeric order as long as there are no leading zeros.
+/// Otherwise leading zeros make the digits larger, so `007` is greater than `10`,
+/// which keeps the ordering consistent with equality.
+impl Ord for Digits {
+    #[inline]
+    fn cmp(&self, other: &Digits) -> Ordering {
+        self.len.cmp(&other.len)
+            .then_with(|| self.as_slice().cmp(other.as_slice()))
+    }
+}
+impl PartialOrd for Digits {
+    #[inline]
+    fn partial_cmp(&self, other: &Digits) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 impl Hash for Digits {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -144,6 +337,15 @@ impl Hash for Digits {
         state.write(self.as_slice());
     }
 }
+impl<'a> IntoIterator for &'a Digits {
+    type Item = u8;
+    type IntoIter = iter::Cloned<slice::Iter<'a, u8>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().cloned()
+    }
+}
 impl Index<usize> for Digits {
     type Output = u8;
 
@@ -162,6 +364,130 @@ unsafe impl NdIndex<IxDyn> for Digits {
         (&self.usize_array()[..(self.len as usize)]).index_unchecked(strides)
     }
 }
+/// The most digits that fit in `MediumDigits`
+pub const MEDIUM_DIGITS_CAPACITY: usize = 40;
+/// Decimal digits stored inline like `Digits`, but with room for up to forty of them.
+///
+/// This avoids allocating a `BigDigits` for numbers that are only a little too large for `Digits`.
+/// Exceeding the capacity panics, just like exceeding the twenty digits of `Digits` does.
+#[derive(Copy)]
+pub struct MediumDigits {
+    len: u8,
+    values: [u8; MEDIUM_DIGITS_CAPACITY]
+}
+impl MediumDigits {
+    #[inline]
+    pub fn new() -> MediumDigits {
+        MediumDigits { len: 0, values: [0; MEDIUM_DIGITS_CAPACITY] }
+    }
+    pub fn from_digits(digits: &[u8]) -> MediumDigits {
+        assert!(digits.len() <= MEDIUM_DIGITS_CAPACITY, "Too many digits: {:?}", digits);
+        let mut result = MediumDigits::new();
+        for &digit in digits {
+            result.push(digit);
+        }
+        result
+    }
+    #[inline]
+    pub fn from_value(num: u64) -> MediumDigits {
+        MediumDigits::from_u128(num as u128)
+    }
+    pub fn from_u128(mut num: u128) -> MediumDigits {
+        let mut result = MediumDigits::new();
+        loop {
+            result.push((num % 10) as u8);
+            num /= 10;
+            if num == 0 { break }
+        }
+        result.reverse();
+        result
+    }
+    /// Split the big integer into its digits, panicking if it needs more than forty of them
+    pub fn from_biguint(value: &BigUint) -> MediumDigits {
+        let text = value.to_str_radix(10);
+        assert!(text.len() <= MEDIUM_DIGITS_CAPACITY, "{} needs more than {} digits", text, MEDIUM_DIGITS_CAPACITY);
+        MediumDigits::from_digits(&text.bytes().map(|b| b - b'0').collect::<Vec<u8>>())
+    }
+    /// Append a digit, panicking if we're already full
+    #[inline]
+    pub fn push(&mut self, digit: u8) {
+        assert!(digit < 10, "Invalid digit: {}", digit);
+        assert!((self.len as usize) < MEDIUM_DIGITS_CAPACITY, "Can't have more than {} digits", MEDIUM_DIGITS_CAPACITY);
+        self.values[self.len as usize] = digit;
+        self.len += 1;
+    }
+    #[inline]
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+    #[inline]
+    pub fn is_palindrome(&self) -> bool {
+        is_palindrome(self.as_slice())
+    }
+    #[inline]
+    pub fn reversed(mut self) -> MediumDigits {
+        self.reverse();
+        self
+    }
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.values[..(self.len as usize)].reverse();
+    }
+    /// The sum of the digits
+    #[inline]
+    pub fn sum(&self) -> u32 {
+        self.iter().map(|&digit| digit as u32).sum()
+    }
+    #[inline]
+    pub fn to_biguint(&self) -> BigUint {
+        digits_to_biguint(self.as_slice())
+    }
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.values[..(self.len as usize)]
+    }
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<u8> {
+        self.as_slice().iter()
+    }
+}
+impl From<Digits> for MediumDigits {
+    #[inline]
+    fn from(digits: Digits) -> MediumDigits {
+        MediumDigits::from_digits(digits.as_slice())
+    }
+}
+// Arrays this large don't implement the standard traits, so we compare the used digits instead
+impl Clone for MediumDigits {
+    #[inline]
+    fn clone(&self) -> MediumDigits {
+        *self
+    }
+}
+impl PartialEq for MediumDigits {
+    #[inline]
+    fn eq(&self, other: &MediumDigits) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl Eq for MediumDigits {}
+impl Hash for MediumDigits {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u8(self.len);
+        state.write(self.as_slice());
+    }
+}
+impl Debug for MediumDigits {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+impl Display for MediumDigits {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_digits(self.as_slice(), f)
+    }
+}
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct BigDigits(Vec<u8>);
 impl BigDigits {
@@ -176,21 +502,19 @@ impl BigDigits {
     pub fn from_value(value: u64) -> BigDigits {
         BigDigits::from(Digits::from_value(value))
     }
-    pub fn from_big_value(mut num: BigInt) -> BigDigits {
-        if num.is_zero() {
-            return BigDigits::from_value(0)
-        }
-        assert!(!num.is_negative());
-        let ten = BigInt::from(10);
-        let mut result = BigDigits(Vec::new());
-        while !num.is_zero() {
-            let (div, digit) = num.div_mod_floor(&ten);
-            let digit = digit.to_u8().unwrap();
-            result.0.push(digit);
-            num = div;
+    pub fn from_u128(mut num: u128) -> BigDigits {
+        let mut result = Vec::with_capacity(39);
+        loop {
+            result.push((num % 10) as u8);
+            num /= 10;
+            if num == 0 { break }
         }
         result.reverse();
-        result
+        BigDigits(result)
+    }
+    /// Split the big integer into its decimal digits, most significant first
+    pub fn from_big_value(value: BigUint) -> BigDigits {
+        BigDigits(value.to_str_radix(10).bytes().map(|digit| digit - b'0').collect())
     }
     #[inline]
     pub fn reverse(&mut self) {
@@ -218,6 +542,51 @@ impl BigDigits {
     pub fn is_palindrome(&self) -> bool {
         is_palindrome(&self.0)
     }
+    /// The sum of the digits, which is a `u64` since there's no limit on their number
+    #[inline]
+    pub fn sum(&self) -> u64 {
+        self.0.iter().map(|&digit| digit as u64).sum()
+    }
+    /// Count how many times each digit appears
+    pub fn digit_counts(&self) -> [u32; 10] {
+        let mut counts = [0; 10];
+        for &digit in &self.0 {
+            counts[digit as usize] += 1;
+        }
+        counts
+    }
+    /// Convert to a `BigUint`, which is the inverse of `from_big_value`
+    #[inline]
+    pub fn to_biguint(&self) -> BigUint {
+        digits_to_biguint(&self.0)
+    }
+    /// The product of the digits, which is zero if any of them are zero
+    pub fn product(&self) -> BigUint {
+        if self.0.contains(&0) { return BigUint::zero() }
+        let mut result = BigUint::from(1u32);
+        for &digit in &self.0 {
+            result = result * BigUint::from(digit);
+        }
+        result
+    }
+}
+/// Writes the digits consecutively, including any leading zeros.
+impl Display for BigDigits {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_digits(&self.0, f)
+    }
+}
+/// Parses a string of decimal digits, preserving any leading zeros.
+///
+/// Unlike `Digits` there's no limit on the number of digits.
+impl FromStr for BigDigits {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<BigDigits, Error> {
+        ensure!(!text.is_empty(), "Empty digits");
+        Ok(BigDigits(text.chars().map(parse_digit).collect::<Result<Vec<u8>, Error>>()?))
+    }
 }
 impl From<Digits> for BigDigits {
     #[inline]
@@ -225,6 +594,42 @@ impl From<Digits> for BigDigits {
         BigDigits(Vec::from(digits.as_slice()))
     }
 }
+impl From<BigUint> for BigDigits {
+    #[inline]
+    fn from(value: BigUint) -> BigDigits {
+        BigDigits::from_big_value(value)
+    }
+}
+/// Converts the digits back into their value, so any leading zeros are lost.
+impl From<BigDigits> for BigUint {
+    #[inline]
+    fn from(digits: BigDigits) -> BigUint {
+        digits.to_biguint()
+    }
+}
+impl<'a> From<&'a BigDigits> for BigUint {
+    #[inline]
+    fn from(digits: &'a BigDigits) -> BigUint {
+        digits.to_biguint()
+    }
+}
+/// Appends the digits, panicking if any of them are invalid (like `from_digits`).
+impl Extend<u8> for BigDigits {
+    fn extend<I: IntoIterator<Item=u8>>(&mut self, digits: I) {
+        self.0.extend(digits.into_iter().map(|digit| {
+            assert!(digit < 10, "Invalid digit: {:?}", digit);
+            digit
+        }));
+    }
+}
+impl iter::FromIterator<u8> for BigDigits {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item=u8>>(digits: I) -> BigDigits {
+        let mut result = BigDigits(Vec::new());
+        result.extend(digits);
+        result
+    }
+}
 impl AddAssign for BigDigits {
     #[inline]
     fn add_assign(&mut self, rhs: BigDigits) {
@@ -268,6 +673,242 @@ fn add_digit(left: u8, right: u8, mut carry: bool) -> (u8, bool) {
     }
     (result, carry)
 }
+impl SubAssign for BigDigits {
+    #[inline]
+    fn sub_assign(&mut self, rhs: BigDigits) {
+        let result = self.clone() - rhs;
+        *self = result;
+    }
+}
+/// Subtracts the digits, panicking if the result would be negative.
+///
+/// Leading zeros are removed from the result, although zero itself is kept as a single digit.
+impl Sub for BigDigits {
+    type Output = BigDigits;
+    fn sub(self, rhs: BigDigits) -> BigDigits {
+        let mut borrow = false;
+        let mut result = Vec::with_capacity(self.0.len().max(rhs.0.len()));
+        for either in self.0.iter().rev().zip_longest(rhs.0.iter().rev()) {
+            let (left, right) = match either {
+                Left(&left) => (left, 0),
+                Right(&right) => (0, right),
+                Both(&left, &right) => (left, right)
+            };
+            let (digit, underflow) = sub_digit(left, right, borrow);
+            borrow = underflow;
+            result.push(digit);
+        }
+        assert!(!borrow, "Negative result subtracting {:?} from {:?}", rhs, self);
+        while result.len() > 1 && result[result.len() - 1] == 0 {
+            result.pop();
+        }
+        result.reverse();
+        let result = BigDigits(result);
+        if let (Some(left), Some(right)) = (self.checked_value(), rhs.checked_value()) {
+            debug_assert_eq!(result.checked_value(), Some(left - right));
+        }
+        result
+    }
+}
+#[inline]
+fn sub_digit(left: u8, right: u8, borrow: bool) -> (u8, bool) {
+    let subtracted = right + (borrow as u8);
+    if left >= subtracted {
+        (left - subtracted, false)
+    } else {
+        (left + 10 - subtracted, true)
+    }
+}
+/// Multiplies the digits using the schoolbook method,
+/// giving at most as many digits as both operands combined.
+impl Mul for BigDigits {
+    type Output = BigDigits;
+    fn mul(self, rhs: BigDigits) -> BigDigits {
+        // Accumulate each column from the least significant digit, then propagate the carries
+        let mut columns = vec![0u32; self.0.len() + rhs.0.len()];
+        for (i, &left) in self.0.iter().rev().enumerate() {
+            for (j, &right) in rhs.0.iter().rev().enumerate() {
+                columns[i + j] += (left as u32) * (right as u32);
+            }
+            // Carry now, so the columns never overflow
+            let mut carry = 0;
+            for column in &mut columns[i..] {
+                *column += carry;
+                carry = *column / 10;
+                *column %= 10;
+            }
+            debug_assert_eq!(carry, 0);
+        }
+        let result = BigDigits::from_reversed_columns(columns);
+        if let (Some(left), Some(right)) = (self.checked_value(), rhs.checked_value()) {
+            debug_assert_eq!(result.checked_value(), left.checked_mul(right));
+        }
+        result
+    }
+}
+impl Mul<u8> for BigDigits {
+    type Output = BigDigits;
+    fn mul(self, rhs: u8) -> BigDigits {
+        let mut columns = Vec::with_capacity(self.0.len() + 3);
+        let mut carry = 0u32;
+        for &digit in self.0.iter().rev() {
+            let product = (digit as u32) * (rhs as u32) + carry;
+            columns.push(product % 10);
+            carry = product / 10;
+        }
+        while carry > 0 {
+            columns.push(carry % 10);
+            carry /= 10;
+        }
+        let result = BigDigits::from_reversed_columns(columns);
+        if let Some(left) = self.checked_value() {
+            debug_assert_eq!(result.checked_value(), left.checked_mul(rhs as u64));
+        }
+        result
+    }
+}
+impl BigDigits {
+    /// Build from the digits in least-significant-first order, removing any leading zeros
+    fn from_reversed_columns(mut columns: Vec<u32>) -> BigDigits {
+        while columns.len() > 1 && columns[columns.len() - 1] == 0 {
+            columns.pop();
+        }
+        if columns.is_empty() {
+            columns.push(0);
+        }
+        BigDigits(columns.into_iter().rev().map(|digit| {
+            debug_assert!(digit < 10);
+            digit as u8
+        }).collect())
+    }
+}
+
+/// Check if the value's digits form a palindrome in the specified radix
+#[inline]
+pub fn is_palindrome_radix(value: u64, radix: u64) -> bool {
+    assert!(radix >= 2, "Invalid radix: {}", radix);
+    let mut remaining = value;
+    let mut reversed = 0u64;
+    while remaining > 0 {
+        // Reversing a palindrome can't overflow, since it gives the same value
+        reversed = match reversed.checked_mul(radix)
+            .and_then(|reversed| reversed.checked_add(remaining % radix)) {
+            Some(reversed) => reversed,
+            None => return false
+        };
+        remaining /= radix;
+    }
+    reversed == value
+}
+
+/// Generate the palindromes with exactly the specified number of digits in ascending order,
+/// by mirroring every possible first half instead of checking each value.
+/// Searches for the largest palindrome can reverse the iterator.
+///
+/// Leading zeros aren't allowed, so zero itself is never included.
+/// This panics unless `base.pow(num_digits)` fits in a `u64`.
+pub fn palindromes_with_digits(num_digits: usize, base: u8) -> impl DoubleEndedIterator<Item=u64> {
+    assert!(base >= 2, "Invalid base: {}", base);
+    assert!(num_digits > 0, "Invalid number of digits");
+    let base = base as u64;
+    assert!(
+        ::num::checked_pow(base, num_digits).is_some(),
+        "Palindromes with {} digits may overflow", num_digits
+    );
+    let half_digits = (num_digits + 1) / 2;
+    let odd = num_digits % 2 == 1;
+    let start = ::num::pow(base, half_digits - 1);
+    let end = start * base;
+    (start..end).map(move |half| {
+        // Append the digits of the half in reverse, skipping the middle digit if there's an odd number
+        let mut result = half;
+        let mut mirrored = if odd { half / base } else { half };
+        while mirrored > 0 {
+            result = result * base + mirrored % base;
+            mirrored /= base;
+        }
+        result
+    })
+}
+
+/// Sum the digits of the value in the specified radix
+#[inline]
+pub fn digit_sum_radix(mut value: u64, radix: u64) -> u64 {
+    assert!(radix >= 2, "Invalid radix: {}", radix);
+    let mut sum = 0;
+    while value > 0 {
+        sum += value % radix;
+        value /= radix;
+    }
+    sum
+}
+
+lazy_static! {
+    static ref DIGIT_TABLE: Vec<u8> = {
+        (0..1000).map(|value| digit_sum_radix(value, 10) as u8).collect()
+    };
+}
+/// Sum the decimal digits of the big integer,
+/// using a lookup table to handle three digits at a time.
+pub fn big_digit_sum(value: &BigUint) -> u64 {
+    let thousand = BigUint::from(1000u64);
+    let table = &**DIGIT_TABLE;
+    let mut target = value.clone();
+    let mut sum = 0;
+    while target > thousand {
+        let (updated_target, modulo) = target.div_mod_floor(&thousand);
+        sum += table[modulo.to_usize().unwrap()] as u64;
+        target = updated_target;
+    }
+    sum += digit_sum_radix(target.to_u64().unwrap(), 10);
+    sum
+}
+/// Sum the decimal digits of the absolute value of the signed big integer
+#[inline]
+pub fn bigint_digit_sum(value: &BigInt) -> u64 {
+    big_digit_sum(&value.abs().to_biguint().unwrap())
+}
+
+/// Below this many values it's faster to just sum each value's digits directly
+const TOTAL_DIGIT_SUM_THRESHOLD: u64 = 1000;
+
+/// The sum of the decimal digit sums of every integer in the range.
+///
+/// Small ranges just call `digit_sum_radix` on each value,
+/// while larger ones compute the contribution of each digit position directly.
+pub fn total_digit_sum(range: RangeInclusive<u64>) -> u64 {
+    let (start, end) = (*range.start(), *range.end());
+    if start > end { return 0 }
+    if end - start < TOTAL_DIGIT_SUM_THRESHOLD {
+        (start..=end).map(|value| digit_sum_radix(value, 10)).sum()
+    } else {
+        let below_start = if start == 0 { 0 } else { cumulative_digit_sum(start - 1) };
+        cumulative_digit_sum(end) - below_start
+    }
+}
+/// The sum of the decimal digit sums of every integer in `0..=limit`
+fn cumulative_digit_sum(limit: u64) -> u64 {
+    /*
+     * Each position cycles through 0-9, spending `power` values on each digit.
+     * The completed cycles above the position contribute 45 * power each,
+     * then the partial cycle contributes every digit below the current one,
+     * plus the current digit for every value in the remaining `low + 1`.
+     */
+    let limit = limit as u128;
+    let mut total = 0u128;
+    let mut power = 1u128;
+    while power <= limit {
+        let high = limit / (power * 10);
+        let current = (limit / power) % 10;
+        let low = limit % power;
+        total += high * 45 * power;
+        total += (current * (current.saturating_sub(1)) / 2) * power;
+        total += current * (low + 1);
+        power *= 10;
+    }
+    assert!(total <= (u64::max_value() as u128), "Overflow summing digits until {}", limit);
+    total as u64
+}
 
 #[cfg(test)]
 mod test {
@@ -280,4 +921,382 @@ mod test {
         assert!(is_palindrome(&[7, 3, 1, 3, 7]));
         assert!(is_palindrome(&[1, 2, 1]));
     }
+    #[test]
+    fn test_palindrome_radix() {
+        assert!(is_palindrome_radix(0, 10));
+        assert!(is_palindrome_radix(585, 10));
+        assert!(is_palindrome_radix(585, 2));
+        assert!(!is_palindrome_radix(586, 10));
+        assert!(!is_palindrome_radix(10, 2));
+        assert!(is_palindrome_radix(0xABA, 16));
+        assert!(is_palindrome_radix(u64::max_value(), 2));
+        assert!(!is_palindrome_radix(u64::max_value() - 1, 10));
+    }
+    #[test]
+    fn medium_digits() {
+        let text = "12345678901234567876543210987654321";
+        let value = BigUint::from_str(text).unwrap();
+        let digits = MediumDigits::from_biguint(&value);
+        assert_eq!(digits.len(), 35);
+        assert!(digits.is_palindrome());
+        assert_eq!(digits.reversed(), digits);
+        assert_eq!(digits.to_string(), text);
+        assert_eq!(digits.to_biguint(), value);
+        assert_eq!(digits.sum(), 2 * (45 + 28) + 8);
+        assert_eq!(digits.sum() as u64, BigDigits::from_str(text).unwrap().sum());
+        let mut not_palindrome = digits;
+        not_palindrome.push(1);
+        assert!(!not_palindrome.is_palindrome());
+        assert_eq!(not_palindrome.reversed().as_slice()[0], 1);
+        assert_eq!(MediumDigits::from_value(12345).as_slice(), &[1, 2, 3, 4, 5]);
+        assert_eq!(MediumDigits::from(Digits::from_value(907)), MediumDigits::from_value(907));
+        assert_eq!(
+            MediumDigits::from_u128(u128::max_value()).to_string(),
+            u128::max_value().to_string()
+        );
+    }
+    #[test]
+    #[should_panic]
+    fn medium_digits_overflow() {
+        MediumDigits::from_biguint(&BigUint::from_str(&"9".repeat(41)).unwrap());
+    }
+    #[test]
+    fn generate_palindromes() {
+        let three_digits = palindromes_with_digits(3, 10).collect_vec();
+        assert_eq!(&three_digits[..4], &[101, 111, 121, 131]);
+        assert_eq!(three_digits.len(), 90);
+        assert_eq!(*three_digits.last().unwrap(), 999);
+        assert_eq!(three_digits, (100..1000).filter(|&n| is_palindrome_radix(n, 10)).collect_vec());
+        assert_eq!(palindromes_with_digits(1, 10).collect_vec(), (1..10).collect_vec());
+        assert_eq!(palindromes_with_digits(2, 10).collect_vec(), (1..10).map(|n| n * 11).collect_vec());
+        assert_eq!(palindromes_with_digits(4, 10).take(3).collect_vec(), vec![1001, 1111, 1221]);
+        assert_eq!(palindromes_with_digits(3, 2).collect_vec(), vec![0b101, 0b111]);
+        assert_eq!(palindromes_with_digits(6, 10).count(), 900);
+        // The largest palindrome made from the product of two 3-digit numbers
+        let largest = palindromes_with_digits(6, 10).rev()
+            .find(|&n| (100..1000).any(|factor| n % factor == 0 && n / factor >= 100 && n / factor < 1000))
+            .unwrap();
+        assert_eq!(largest, 906_609);
+    }
+    #[test]
+    fn test_radix() {
+        assert_eq!(Digits::from_value_radix(10, 2).as_slice(), &[1, 0, 1, 0]);
+        assert_eq!(Digits::from_value_radix(0, 2).as_slice(), &[0]);
+        assert_eq!(Digits::from_value_radix(0xBEEF, 16).as_slice(), &[11, 14, 14, 15]);
+        assert_eq!(Digits::from_value_radix(0xBEEF, 16).value_radix(16), 0xBEEF);
+        // The largest value that fits in twenty binary digits
+        assert_eq!(Digits::from_value_radix((1 << 20) - 1, 2).as_slice(), &[1; 20]);
+        assert_eq!(Digits::from_value_radix(u64::max_value(), 16).value_radix(16), u64::max_value());
+        let binary = Digits::from_value_radix(585, 2);
+        assert_eq!(binary.as_slice(), &[1, 0, 0, 1, 0, 0, 1, 0, 0, 1]);
+        assert!(binary.is_palindrome());
+        assert!(!Digits::from_value_radix(586, 2).is_palindrome());
+        for value in 0..1000 {
+            assert_eq!(Digits::from_value(value), Digits::from_value_radix(value, 10));
+            assert_eq!(Digits::from_value_radix(value, 7).value_radix(7), value);
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn test_radix_overflow() {
+        Digits::from_value_radix(1 << 20, 2);
+    }
+    #[test]
+    fn test_parse() {
+        for &value in &[0, 7, 12345, 1_000_000, u64::max_value()] {
+            let text = value.to_string();
+            let digits = text.parse::<Digits>().unwrap();
+            assert_eq!(digits, Digits::from_value(value));
+            assert_eq!(digits.value(), value);
+            assert_eq!(text.parse::<BigDigits>().unwrap(), BigDigits::from_value(value));
+        }
+        let padded = "007".parse::<Digits>().unwrap();
+        assert_eq!(padded.len(), 3);
+        assert_eq!(padded.as_slice(), &[0, 0, 7]);
+        assert_eq!(padded.value(), 7);
+        assert_eq!("007".parse::<BigDigits>().unwrap().as_slice(), &[0, 0, 7]);
+        let long = "1".repeat(50);
+        assert_eq!(long.parse::<BigDigits>().unwrap().as_slice(), &[1; 50][..]);
+        assert!(long.parse::<Digits>().is_err());
+        assert!("9".repeat(20).parse::<Digits>().is_ok());
+        for invalid in &["", "12a4", "-12", " 12", "１２"] {
+            assert!(invalid.parse::<Digits>().is_err(), "Parsed {:?}", invalid);
+            assert!(invalid.parse::<BigDigits>().is_err(), "Parsed {:?}", invalid);
+        }
+    }
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", Digits::from_value(12345)), "12345");
+        assert_eq!(format!("{}", Digits::new()), "");
+        assert_eq!(format!("{}", BigDigits::from_value(12345)), "12345");
+        for text in &["0", "007", "12345", "18446744073709551615", "99999999999999999999"] {
+            assert_eq!(text.parse::<Digits>().unwrap().to_string(), *text);
+            assert_eq!(text.parse::<BigDigits>().unwrap().to_string(), *text);
+        }
+        let long = "31415926535897932384626433832795028841971693993751";
+        assert_eq!(long.parse::<BigDigits>().unwrap().to_string(), long);
+    }
+    #[test]
+    fn test_to_biguint() {
+        assert_eq!(Digits::from_value(12345).to_biguint(), BigUint::from(12345u32));
+        assert_eq!(Digits::from_value(0).to_biguint(), BigUint::zero());
+        assert_eq!(Digits::new().to_biguint(), BigUint::zero());
+        let nines = Digits::from_digits(&[9; 20]);
+        assert_eq!(nines.checked_value(), None);
+        assert_eq!(nines.to_biguint(), ::num::pow::pow(BigUint::from(10u32), 20) - BigUint::from(1u32));
+        let text = "31415926535897932384626433832795028841971693993751";
+        let digits = text.parse::<BigDigits>().unwrap();
+        let value = digits.to_biguint();
+        assert_eq!(value.to_string(), text);
+        assert_eq!(BigDigits::from_big_value(value), digits);
+    }
+    #[test]
+    fn test_pandigital() {
+        assert!(Digits::from_value(2143).is_pandigital(4));
+        assert!(!Digits::from_value(2133).is_pandigital(4));
+        assert!(!Digits::from_value(2143).is_pandigital(5));
+        assert!(!Digits::from_value(21435).is_pandigital(4));
+        assert!(Digits::from_value(1).is_pandigital(1));
+        assert!(Digits::from_value(918_273_645).is_pandigital(9));
+        assert!(!Digits::from_value(918_273_640).is_pandigital(9));
+        assert!(Digits::from_value(1_406_357_289).is_pandigital_0_to_9());
+        assert!("0123456789".parse::<Digits>().unwrap().is_pandigital_0_to_9());
+        assert!(!Digits::from_value(1_406_357_288).is_pandigital_0_to_9());
+        assert!(!Digits::from_value(123_456_789).is_pandigital_0_to_9());
+    }
+    #[test]
+    fn test_ordering() {
+        assert!(!(Digits::from_value(89) > Digits::from_value(100)));
+        assert!(Digits::from_value(89) < Digits::from_value(100));
+        assert!(Digits::from_value(123) < Digits::from_value(124));
+        assert_eq!(Digits::from_value(42).cmp(&Digits::from_value(42)), Ordering::Equal);
+        // Leading zeros count as extra digits
+        assert!("007".parse::<Digits>().unwrap() > Digits::from_value(10));
+        let mut values = vec![5, 1000, 42, 0, 999, 100, 7, u64::max_value(), 41, 1];
+        let mut digits = values.iter().map(|&value| Digits::from_value(value)).collect::<Vec<_>>();
+        values.sort();
+        digits.sort();
+        assert_eq!(digits.iter().map(Digits::value).collect::<Vec<_>>(), values);
+    }
+    #[test]
+    fn test_set() {
+        let mut digits = Digits::from_value(12345);
+        digits.set(0, 9);
+        digits.set(4, 0);
+        assert_eq!(digits.as_slice(), &[9, 2, 3, 4, 0]);
+        assert_eq!(digits.len(), 5);
+    }
+    #[test]
+    fn test_insert() {
+        let mut digits = Digits::from_value(1245);
+        digits.insert(2, 3);
+        assert_eq!(digits.as_slice(), &[1, 2, 3, 4, 5]);
+        digits.insert(0, 0);
+        digits.insert(6, 6);
+        assert_eq!(digits.as_slice(), &[0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(digits.len(), 7);
+        let mut empty = Digits::new();
+        empty.insert(0, 7);
+        assert_eq!(empty.as_slice(), &[7]);
+    }
+    #[test]
+    #[should_panic]
+    fn test_insert_overflow() {
+        let mut digits = Digits::from_digits(&[1; 20]);
+        digits.insert(0, 1);
+    }
+    #[test]
+    fn test_sub() {
+        let sub = |left: &str, right: &str| {
+            (left.parse::<BigDigits>().unwrap() - right.parse::<BigDigits>().unwrap()).to_string()
+        };
+        assert_eq!(sub("100", "1"), "99");
+        assert_eq!(sub("1000000", "999999"), "1");
+        assert_eq!(sub("12345", "12345"), "0");
+        assert_eq!(sub("0", "0"), "0");
+        assert_eq!(sub("54321", "0"), "54321");
+        assert_eq!(sub("100000000000000000000000000000", "1"), "99999999999999999999999999999");
+        assert_eq!(sub("100000000000000000000000000000", "99999999999999999999999999999"), "1");
+        let mut digits = BigDigits::from_value(1000);
+        digits -= BigDigits::from_value(1);
+        assert_eq!(digits, BigDigits::from_value(999));
+    }
+    #[test]
+    #[should_panic]
+    fn test_sub_negative() {
+        let _ = BigDigits::from_value(99) - BigDigits::from_value(100);
+    }
+    #[test]
+    fn test_mul() {
+        assert_eq!(BigDigits::from_value(123) * BigDigits::from_value(456), BigDigits::from_value(56088));
+        assert_eq!(BigDigits::from_value(0) * BigDigits::from_value(456), BigDigits::from_value(0));
+        assert_eq!(BigDigits::from_value(99) * BigDigits::from_value(99), BigDigits::from_value(9801));
+        let max = BigDigits::from_value(u64::max_value());
+        let squared = (max.clone() * max).to_string();
+        assert_eq!(squared, (BigUint::from(u64::max_value()) * BigUint::from(u64::max_value())).to_string());
+        assert_eq!(squared.len(), 39);
+        assert_eq!(BigDigits::from_value(123) * 7u8, BigDigits::from_value(861));
+        assert_eq!(BigDigits::from_value(999) * 255u8, BigDigits::from_value(254_745));
+        assert_eq!(BigDigits::from_value(999) * 0u8, BigDigits::from_value(0));
+        // 30! overflows a u64
+        let factorial = (1..31u8).fold(BigDigits::from_value(1), |product, n| product * n);
+        assert_eq!(factorial.to_biguint(), ::utils::factorial(30));
+    }
+    #[test]
+    fn test_iter() {
+        let digits = Digits::from_value(12345);
+        assert_eq!(digits.iter().cloned().collect::<Vec<u8>>(), digits.as_slice());
+        assert_eq!(digits.iter().rev().cloned().collect::<Vec<u8>>(), vec![5, 4, 3, 2, 1]);
+        let mut collected = Vec::new();
+        for digit in &digits {
+            collected.push(digit);
+        }
+        assert_eq!(collected, digits.as_slice());
+        assert_eq!(Digits::new().iter().count(), 0);
+    }
+    #[test]
+    fn test_digit_counts() {
+        assert_eq!(Digits::from_value(112233).digit_counts(), [0, 2, 2, 2, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Digits::from_value(0).digit_counts(), [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        // Permutations of each other have the same counts
+        assert_eq!(Digits::from_value(125874).digit_counts(), Digits::from_value(251748).digit_counts());
+        assert_ne!(Digits::from_value(125874).digit_counts(), Digits::from_value(251749).digit_counts());
+        assert_eq!(BigDigits::from_value(112233).digit_counts(), [0, 2, 2, 2, 0, 0, 0, 0, 0, 0]);
+        let nines = BigDigits::from_digits(&[9; 300]);
+        assert_eq!(nines.digit_counts()[9], 300);
+    }
+    #[test]
+    #[should_panic(expected = "decimal digits")]
+    fn hex_digit_counts() {
+        Digits::from_value_radix(255, 16).digit_counts();
+    }
+    #[test]
+    #[should_panic(expected = "decimal digits")]
+    fn hex_pandigital() {
+        Digits::from_value_radix(0x1_2345_678a, 16).is_pandigital(9);
+    }
+    #[test]
+    fn display_radix() {
+        assert_eq!(Digits::from_value_radix(255, 16).to_string(), "ff");
+        assert_eq!(Digits::from_value_radix(0xBEEF, 16).to_string(), "beef");
+        assert_eq!(Digits::from_value_radix(10, 2).to_string(), "1010");
+        assert_eq!(Digits::from_value_radix(35, 36).to_string(), "z");
+    }
+    #[test]
+    fn test_rotations() {
+        let rotations = Digits::from_value(197).rotations().map(|digits| digits.value()).collect_vec();
+        assert_eq!(rotations, vec![197, 971, 719]);
+        assert_eq!(Digits::from_value(1234).rotated_left(1).value(), 2341);
+        assert_eq!(Digits::from_value(1234).rotated_left(6).value(), 3412);
+        assert_eq!(Digits::from_value(1234).rotated_left(4).value(), 1234);
+        let rotated = Digits::from_value(105).rotated_left(1);
+        assert_eq!(rotated.as_slice(), &[0, 5, 1]);
+        assert_eq!(rotated.value(), 51);
+        assert_eq!(Digits::from_value(111).rotations().count(), 3);
+        assert_eq!(Digits::new().rotations().count(), 0);
+        assert_eq!(Digits::new().rotated_left(3), Digits::new());
+    }
+    #[test]
+    fn test_from_u128() {
+        assert_eq!(Digits::from_u128(0), Digits::from_value(0));
+        assert_eq!(Digits::from_u128(12345), Digits::from_value(12345));
+        let largest = 10u128.pow(20) - 1;
+        assert_eq!(Digits::from_u128(largest), Digits::from_digits(&[9; 20]));
+        assert_eq!(Digits::from_u128(largest).to_biguint().to_string(), largest.to_string());
+        assert_eq!(BigDigits::from_u128(0), BigDigits::from_value(0));
+        assert_eq!(BigDigits::from_u128(u128::max_value()).to_string(), u128::max_value().to_string());
+    }
+    #[test]
+    #[should_panic]
+    fn test_from_u128_overflow() {
+        Digits::from_u128(10u128.pow(20));
+    }
+    #[test]
+    fn biguint_conversions() {
+        use rand::{Rng, SeedableRng, XorShiftRng};
+        let mut rng = XorShiftRng::from_seed([0x5eed_1234, 0x193a_6754, 0xa8a7_d469, 0x9783_0e05]);
+        for _ in 0..500 {
+            let len = rng.gen_range(0, 12);
+            let value = BigUint::new((0..len).map(|_| rng.gen::<u32>()).collect());
+            let digits = BigDigits::from(value.clone());
+            assert_eq!(digits.to_string(), value.to_str_radix(10));
+            assert_eq!(BigUint::from(&digits), value);
+            let converted: BigUint = digits.into();
+            assert_eq!(converted, value);
+        }
+        assert_eq!(BigUint::from("007".parse::<BigDigits>().unwrap()), BigUint::from(7u32));
+    }
+    #[test]
+    fn collect_big_digits() {
+        assert_eq!((0..9).collect::<BigDigits>(), BigDigits::from_digits(&[0, 1, 2, 3, 4, 5, 6, 7, 8]));
+        let mut digits = BigDigits::from_value(12);
+        digits.extend(vec![3, 4]);
+        digits.extend(Digits::from_value(567).iter().cloned());
+        assert_eq!(digits, BigDigits::from_value(1234567));
+        assert_eq!(iter::repeat(9).take(50).collect::<BigDigits>(), BigDigits::from_digits(&[9; 50]));
+    }
+    #[test]
+    #[should_panic]
+    fn collect_invalid_digit() {
+        let _ = (5..12).collect::<BigDigits>();
+    }
+    #[test]
+    fn test_from_big_value() {
+        assert_eq!(BigDigits::from_big_value(12345u64.into()), BigDigits::from_value(12345));
+        assert_eq!(BigDigits::from_big_value(BigUint::zero()), BigDigits::from_value(0));
+        let text = "123456789012345678901234567890123456789012345678901234567890";
+        let value = text.parse::<BigUint>().unwrap();
+        let digits = BigDigits::from_big_value(value.clone());
+        assert_eq!(digits.as_slice().len(), 60);
+        assert_eq!(digits, text.parse::<BigDigits>().unwrap());
+        assert_eq!(digits.to_biguint(), value);
+    }
+    #[test]
+    fn test_sum() {
+        assert_eq!(Digits::from_value(12345).sum(), 15);
+        assert_eq!(Digits::from_value(0).sum(), 0);
+        assert_eq!(Digits::from_value(u64::max_value()).sum(), 87);
+        assert_eq!(BigDigits::from_value(12345).sum(), 15);
+        let power = ::num::pow::pow(BigUint::from(2u32), 1000);
+        assert_eq!(BigDigits::from_big_value(power).sum(), 1366);
+    }
+    #[test]
+    fn test_product() {
+        assert_eq!(Digits::from_value(234).product(), 24);
+        assert_eq!(Digits::from_value(7).product(), 7);
+        assert_eq!(Digits::from_value(2304).product(), 0);
+        // Twenty nines is the largest product
+        assert_eq!(Digits::from_digits(&[9; 20]).product(), 9u64.pow(20));
+        assert_eq!(BigDigits::from_value(234).product(), BigUint::from(24u32));
+        assert_eq!(BigDigits::from_value(2304).product(), BigUint::zero());
+        let nines = BigDigits::from_digits(&[9; 50]);
+        assert_eq!(nines.product(), ::num::pow::pow(BigUint::from(9u32), 50));
+    }
+    #[test]
+    fn test_big_digit_sum() {
+        assert_eq!(big_digit_sum(&BigUint::from(0u64)), 0);
+        assert_eq!(big_digit_sum(&BigUint::from(1000u64)), 1);
+        assert_eq!(big_digit_sum(&BigUint::from(987_654_321u64)), 45);
+        // 2^1000
+        assert_eq!(big_digit_sum(&::num::pow::pow(BigUint::from(2u64), 1000)), 1366);
+        // 100!
+        assert_eq!(big_digit_sum(&::utils::factorial(100)), 648);
+    }
+    #[test]
+    fn test_bigint_digit_sum() {
+        assert_eq!(bigint_digit_sum(&BigInt::from(-1234)), 10);
+        assert_eq!(bigint_digit_sum(&BigInt::from(1234)), 10);
+        assert_eq!(bigint_digit_sum(&BigInt::from(0)), 0);
+    }
+    #[test]
+    fn test_total_digit_sum() {
+        // 1 + 2 + ... + 9, then 1 + 2 + 3 + 4 for 10 through 13
+        assert_eq!(total_digit_sum(1..=13), 55);
+        assert_eq!(total_digit_sum(0..=0), 0);
+        let naive = |start: u64, end: u64| (start..=end)
+            .map(|value| digit_sum_radix(value, 10))
+            .sum::<u64>();
+        assert_eq!(total_digit_sum(17..=12345), naive(17, 12345));
+        assert_eq!(total_digit_sum(0..=99_999), naive(0, 99_999));
+    }
 }
@@ -0,0 +1,48 @@
+//! Parsing for the problems that give a grid of numbers as input
+use std::str::FromStr;
+
+use failure::Error;
+use ndarray::Array2;
+
+/// Parse rows of whitespace-separated numbers into a two-dimensional array,
+/// ignoring any blank lines.
+///
+/// Every row must have the same number of columns.
+pub fn parse_grid(text: &str) -> Result<Array2<u64>, Error> {
+    let mut values = Vec::new();
+    let mut rows = 0;
+    let mut columns = None;
+    for (line_number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() { continue }
+        let mut row_length = 0;
+        for token in line.split_whitespace() {
+            let value = u64::from_str(token)
+                .map_err(|cause| format_err!("Invalid number {:?} on line {}: {}", token, line_number + 1, cause))?;
+            values.push(value);
+            row_length += 1;
+        }
+        let expected = *columns.get_or_insert(row_length);
+        ensure!(
+            row_length == expected,
+            "Expected {} columns on line {}, but got {}",
+            expected, line_number + 1, row_length
+        );
+        rows += 1;
+    }
+    Ok(Array2::from_shape_vec((rows, columns.unwrap_or(0)), values)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn parse() {
+        let grid = parse_grid("08 02 22\n49 49 99\n").unwrap();
+        assert_eq!(grid.dim(), (2, 3));
+        assert_eq!(grid[[0, 0]], 8);
+        assert_eq!(grid[[1, 2]], 99);
+        assert_eq!(parse_grid("").unwrap().dim(), (0, 0));
+        assert!(parse_grid("1 2 3\n4 5\n").is_err());
+        assert!(parse_grid("1 2 x\n").is_err());
+    }
+}
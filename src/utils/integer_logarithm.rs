@@ -77,6 +77,36 @@ pub trait IntegerLogarithm: Clone + FromPrimitive + Integer + ::std::fmt::Debug
         }
         real_log
     }
+    /// The floor of the logarithm in the specified base, found by repeated division
+    fn floor_log(&self, base: u64) -> u64 {
+        assert!(base >= 2, "Invalid base: {}", base);
+        assert!(*self > Self::zero(), "Invalid value: {:?}", self);
+        let base = match Self::from_u64(base) {
+            Some(base) => base,
+            // The base is larger than any value we could have
+            None => return 0
+        };
+        let mut log = 0;
+        let mut remaining = self.clone();
+        while remaining >= base {
+            remaining = remaining / base.clone();
+            log += 1;
+        }
+        log
+    }
+    /// The ceiling of the logarithm in the specified base,
+    /// which is one more than `floor_log` unless the value is an exact power.
+    fn ceil_log(&self, base: u64) -> u64 {
+        let log = self.floor_log(base);
+        let mut power = Self::one();
+        if let Some(base) = Self::from_u64(base) {
+            for _ in 0..log {
+                // Never overflows, since the power is at most the value
+                power = power * base.clone();
+            }
+        }
+        if power == *self { log } else { log + 1 }
+    }
     fn abs(&self) -> Self {
         let result = match Self::from_i8(-1) {
             Some(neg_1) => {
@@ -171,7 +201,7 @@ macro_rules! prim_int_lograithm {
         })*
     }
 }
-prim_int_lograithm!(i32, u32, i64, u64);
+prim_int_lograithm!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize, i128, u128);
 
 #[cfg(test)]
 mod test {
@@ -194,6 +224,55 @@ mod test {
         assert_eq!(u32::exp2(31) - 1, i32::max_value() as u32);
     }
     #[test]
+    fn more_primitive_types() {
+        assert_eq!(usize::exp2(10), 1024);
+        assert_eq!(1024usize.floor_log2(), 10);
+        assert_eq!(u8::max_value().floor_log2(), 7);
+        assert_eq!(u8::max_value().ceil_log2(), 8);
+        assert_eq!(200u8.floor_log10(), 2);
+        assert_eq!(1000u16.floor_log10(), 3);
+        assert_eq!(IntegerLogarithm::count_decimal_digits(&-99i8), 2);
+        assert_eq!(IntegerLogarithm::count_decimal_digits(&-12345isize), 5);
+        assert_eq!(u128::exp2(100).floor_log2(), 100);
+        assert_eq!(u128::exp2(100).ceil_log2(), 100);
+        assert_eq!((u128::exp2(100) + 1).ceil_log2(), 101);
+        assert_eq!(u128::max_value().floor_log2(), 127);
+        assert_eq!(u128::max_value().ceil_log2(), 128);
+        assert_eq!(u128::exp10(30).floor_log10(), 30);
+        assert_eq!(u128::max_value().floor_log10(), 38);
+        assert_eq!(i128::max_value().floor_log2(), 126);
+    }
+    #[test]
+    fn integer_log_base() {
+        assert_eq!(1000u64.floor_log(10), 3);
+        assert_eq!(1000u64.ceil_log(10), 3);
+        assert_eq!(1001u64.ceil_log(10), 4);
+        assert_eq!(1023u64.floor_log(2), 9);
+        assert_eq!(1023u64.ceil_log(2), 10);
+        assert_eq!(1024u64.ceil_log(2), 10);
+        assert_eq!(80u64.floor_log(3), 3);
+        assert_eq!(81u64.floor_log(3), 4);
+        assert_eq!(81u64.ceil_log(3), 4);
+        assert_eq!(82u64.ceil_log(3), 5);
+        assert_eq!(1u64.floor_log(7), 0);
+        assert_eq!(1u64.ceil_log(7), 0);
+        assert_eq!(u64::max_value().floor_log(2), 63);
+        assert_eq!(u64::max_value().ceil_log(2), 64);
+        assert_eq!(u64::max_value().floor_log(10), 19);
+        // The base doesn't fit in the type
+        assert_eq!(200u8.floor_log(1000), 0);
+        assert_eq!(200u8.ceil_log(1000), 1);
+        let i = BigUint::exp10(100);
+        assert_eq!(i.floor_log(10), 100);
+        assert_eq!(i.ceil_log(10), 100);
+        assert_eq!(i.floor_log(1000), 33);
+    }
+    #[test]
+    #[should_panic]
+    fn integer_log_invalid_base() {
+        100u64.floor_log(1);
+    }
+    #[test]
     fn integer_exp10() {
         assert_eq!(i32::exp10(0), 1);
         assert_eq!(i32::exp10(1), 10);
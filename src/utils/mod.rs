@@ -5,83 +5,248 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::ops::{Index, Add, AddAssign};
 use num::{PrimInt, Integer, Signed, Zero, ToPrimitive, FromPrimitive, NumCast, BigInt, BigUint};
-use std::time::Instant;
+use num::rational::Ratio;
+use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use itertools::EitherOrBoth::*;
 
 pub mod primes;
+pub mod text;
+pub mod grid;
+pub mod partitions;
+pub mod roman;
+pub mod words;
 mod digits;
 mod integer_logarithm;
 mod continued_fraction;
 
-pub use self::digits::{Digits, BigDigits};
+pub use self::digits::{
+    Digits, MediumDigits, BigDigits, digit_sum_radix, total_digit_sum, is_palindrome_radix,
+    palindromes_with_digits, big_digit_sum, bigint_digit_sum
+};
 pub use self::integer_logarithm::IntegerLogarithm;
-pub use self::continued_fraction::ContinuedFraction;
+pub use self::continued_fraction::{ContinuedFraction, e_convergent_numerator_digit_sum};
 
 const ASSERT_ROTATE_INDEXES: bool = cfg!(debug_assertions);
 
 /// Cartesian product of input values
 pub fn product<T: Clone>(args: &[T], repeat: usize) -> Vec<Vec<T>> {
-    // Based of python itertools
-    let pools = vec![args; repeat];
-    let mut result = vec![vec![]];
-    for pool in pools {
-        let mut updated_result = Vec::with_capacity(result.len() * pool.len());
-        for x in &result {
-            for y in pool {
-                let mut item = x.clone();
-                item.push(y.clone());
-                updated_result.push(item);
+    product_iter(args, repeat).collect()
+}
+/// Lazily generate the cartesian product of the input values,
+/// in the same order as `product` but without storing all of them at once.
+pub fn product_iter<'a, T: Clone>(args: &'a [T], repeat: usize) -> impl Iterator<Item=Vec<T>> + 'a {
+    Product {
+        args, counters: vec![0; repeat],
+        // There's nothing to choose from (unless we're choosing nothing)
+        finished: args.is_empty() && repeat > 0
+    }
+}
+/// An odometer over the indexes of the values, where the last index changes the fastest
+struct Product<'a, T: 'a> {
+    args: &'a [T],
+    counters: Vec<usize>,
+    finished: bool
+}
+impl<'a, T: Clone> Iterator for Product<'a, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.finished { return None }
+        let result = self.counters.iter()
+            .map(|&index| self.args[index].clone())
+            .collect();
+        // Advance the odometer, finishing once every counter rolls over
+        self.finished = true;
+        for counter in self.counters.iter_mut().rev() {
+            *counter += 1;
+            if *counter < self.args.len() {
+                self.finished = false;
+                break
             }
+            *counter = 0;
         }
-        result = updated_result;
+        Some(result)
     }
-    result
 }
 
+/// The binomial coefficient `n choose k`, panicking if it doesn't fit in a `u64`
+pub fn binomial(n: u64, k: u64) -> u64 {
+    if k > n { return 0 }
+    let k = k.min(n - k);
+    let mut result = 1u128;
+    for i in 0..k {
+        // Always exact, since the result is the binomial coefficient of (n - k + i + 1, i + 1)
+        result = result * (n - k + i + 1) as u128 / (i + 1) as u128;
+        assert!(result <= u64::max_value() as u128, "Binomial overflowed for {} choose {}", n, k);
+    }
+    result as u64
+}
+/// The number of ways to choose `k` of `n` items, ignoring the order they were chosen in
+#[inline]
+pub fn combinations_count(n: u64, k: u64) -> u64 {
+    binomial(n, k)
+}
+/// Lazily generate every way to choose `k` of the items, ignoring order.
+///
+/// The combinations are given in lexicographic order of their indexes,
+/// so they're sorted if the items are.
+pub fn combinations_iter<'a, T: Clone>(items: &'a [T], k: usize) -> impl Iterator<Item=Vec<T>> + 'a {
+    Combinations { items, indexes: (0..k).collect(), finished: k > items.len() }
+}
+struct Combinations<'a, T: 'a> {
+    items: &'a [T],
+    indexes: Vec<usize>,
+    finished: bool
+}
+impl<'a, T: Clone> Iterator for Combinations<'a, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.finished { return None }
+        let result = self.indexes.iter()
+            .map(|&index| self.items[index].clone())
+            .collect();
+        // Find the last index that can still be increased, then reset the ones after it
+        let (n, k) = (self.items.len(), self.indexes.len());
+        match (0..k).rev().find(|&i| self.indexes[i] < n - k + i) {
+            Some(i) => {
+                self.indexes[i] += 1;
+                for j in (i + 1)..k {
+                    self.indexes[j] = self.indexes[j - 1] + 1;
+                }
+            },
+            None => self.finished = true
+        }
+        Some(result)
+    }
+}
 pub fn permutations<T: Clone>(values: Vec<T>, k: usize) -> Vec<Vec<T>> {
     let timer = DebugTimer::start();
-    let mut result = Vec::new();
-    permutation_indexes(k, values.len(), |indexes| {
-        result.push(indexes.iter().map(|&index| values[index].clone()).collect())
-    });
-    timer.finish_with(|| format!("Computed {} permutations of {} values", k, values.len()));
+    let num_values = values.len();
+    let result = permutations_iter(values, k).collect_vec();
+    timer.finish_with(|| format!("Computed {} permutations of {} values", k, num_values));
     result
 }
-fn permutation_indexes<F: FnMut(&[usize])>(k: usize, n: usize, mut func: F) {
-    assert!(k <= n);
-    // From python itertools
-    let mut indexes = (0..n).collect::<Vec<_>>();
-    let mut cycles = (n - k + 1..=n).rev().collect::<Vec<_>>();
-    func(&indexes[..k]);
-    'outer: loop {
-        for i in (0..k).rev() {
-            cycles[i] -= 1;
-            if cycles[i] == 0 {
+/// Lazily generate the permutations of length `k`,
+/// in the same order as `permutations` but without storing all of them at once.
+pub fn permutations_iter<T: Clone>(values: Vec<T>, k: usize) -> impl Iterator<Item=Vec<T>> {
+    let indexes = PermutationIndexes::new(k, values.len());
+    Permutations { values, indexes }
+}
+struct Permutations<T> {
+    values: Vec<T>,
+    indexes: PermutationIndexes
+}
+impl<T: Clone> Iterator for Permutations<T> {
+    type Item = Vec<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Vec<T>> {
+        let values = &self.values;
+        self.indexes.advance().map(|indexes| {
+            indexes.iter().map(|&index| values[index].clone()).collect()
+        })
+    }
+}
+/// Directly compute the n-th (zero-indexed) lexicographic permutation of the values,
+/// using the factorial number system instead of generating the earlier permutations.
+///
+/// The values are assumed to already be in sorted order,
+/// and this gives `None` if there are fewer than `n + 1` permutations.
+pub fn nth_permutation<T: Clone>(values: &[T], mut n: u64) -> Option<Vec<T>> {
+    if let Some(count) = factorial_u64(values.len() as u64) {
+        if n >= count { return None }
+    }
+    let mut remaining = values.to_vec();
+    let mut result = Vec::with_capacity(values.len());
+    for i in (0..values.len() as u64).rev() {
+        // If the factorial overflows, it's larger than any possible n
+        let index = match factorial_u64(i) {
+            Some(factorial) => {
+                let index = n / factorial;
+                n %= factorial;
+                index
+            },
+            None => 0
+        };
+        result.push(remaining.remove(index as usize));
+    }
+    Some(result)
+}
+/// The zero-indexed position of the permutation among all the lexicographic permutations of its values,
+/// which is the inverse of `nth_permutation`.
+///
+/// The values must be distinct, and this panics if the rank doesn't fit in a `u64`.
+pub fn permutation_rank<T: Ord>(permutation: &[T]) -> u64 {
+    let mut rank = 0u64;
+    for (i, value) in permutation.iter().enumerate() {
+        // Count the smaller values that haven't been used yet
+        let smaller = permutation[i + 1..].iter()
+            .filter(|&other| other < value)
+            .count() as u64;
+        debug_assert!(!permutation[i + 1..].contains(value), "Duplicate values");
+        if smaller > 0 {
+            let remaining = (permutation.len() - i - 1) as u64;
+            rank = factorial_u64(remaining)
+                .and_then(|factorial| factorial.checked_mul(smaller))
+                .and_then(|amount| rank.checked_add(amount))
+                .unwrap_or_else(|| panic!("Rank overflowed for {} values", permutation.len()));
+        }
+    }
+    rank
+}
+/// The state of generating permutation indexes, one permutation at a time
+struct PermutationIndexes {
+    indexes: Vec<usize>,
+    cycles: Vec<usize>,
+    k: usize,
+    started: bool,
+    finished: bool
+}
+impl PermutationIndexes {
+    fn new(k: usize, n: usize) -> PermutationIndexes {
+        assert!(k <= n);
+        // From python itertools
+        PermutationIndexes {
+            indexes: (0..n).collect(),
+            cycles: (n - k + 1..=n).rev().collect(),
+            k, started: false, finished: false
+        }
+    }
+    fn advance(&mut self) -> Option<&[usize]> {
+        if self.finished { return None }
+        if !self.started {
+            self.started = true;
+            return Some(&self.indexes[..self.k])
+        }
+        let n = self.indexes.len();
+        for i in (0..self.k).rev() {
+            self.cycles[i] -= 1;
+            if self.cycles[i] == 0 {
                 // indices[i:] = indices[i+1:] + indices[i:i+1]
                 let expected = if ASSERT_ROTATE_INDEXES {
-                    Some(indexes[i+1..].iter()
-                        .chain(&indexes[i..(i+1)])
+                    Some(self.indexes[i+1..].iter()
+                        .chain(&self.indexes[i..(i+1)])
                         .cloned().collect_vec()
                     )
                 } else {
                     None
                 };
-                indexes[i..].rotate_left(1);
+                self.indexes[i..].rotate_left(1);
                 if let Some(expected) = expected {
-                    assert_eq!(*expected, indexes[i..]);
+                    assert_eq!(*expected, self.indexes[i..]);
                 }
-                cycles[i] = n - i;
+                self.cycles[i] = n - i;
             } else {
-                let j = cycles[i];
-                let num_indexes = indexes.len();
-                indexes.swap(i, num_indexes - j);
-                func(&indexes[..k]);
-                continue 'outer;
+                let j = self.cycles[i];
+                self.indexes.swap(i, n - j);
+                return Some(&self.indexes[..self.k])
             }
         }
-        return;
+        self.finished = true;
+        None
     }
 }
 
@@ -99,6 +264,22 @@ impl DebugTimer {
         };
         DebugTimer { start }
     }
+    /// Start the timer even if debug logging is disabled
+    #[inline]
+    pub fn force_start() -> Self {
+        DebugTimer { start: Some(Instant::now()) }
+    }
+    /// The time elapsed since the timer started, or `None` if timing isn't enabled
+    #[inline]
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.start.map(|start| start.elapsed())
+    }
+    /// Finish the timer, logging the message and giving the elapsed time if timing is enabled
+    pub fn finish_returning(self, msg: &::std::fmt::Display) -> Option<Duration> {
+        let elapsed = self.elapsed();
+        self.finish(msg);
+        elapsed
+    }
     #[inline]
     pub fn finish_with<F, T>(self, mut msg: F) where F: FnMut() -> T, T: ::std::fmt::Display {
         if self.start.is_some() {
@@ -118,6 +299,12 @@ impl DebugTimer {
 
 pub use self::primes::{prime_set, primes};
 
+/// Multiply the values modulo the specified value, without overflowing
+#[inline]
+pub fn mul_mod(left: u64, right: u64, modulus: u64) -> u64 {
+    ((left as u128) * (right as u128) % (modulus as u128)) as u64
+}
+
 pub fn modular_pow(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
     // NOTE: Taken from wikipedia
     assert_ne!(modulus, 0);
@@ -126,14 +313,548 @@ pub fn modular_pow(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
     base %= modulus;
     while exponent > 0 {
         if exponent % 2 == 1 {
-            result = (result * base) % modulus;
+            result = mul_mod(result, base, modulus);
         }
         exponent >>= 1;
-        base = (base * base) % modulus;
+        base = mul_mod(base, base, modulus);
+    }
+    result
+}
+
+/// The greatest common divisor of the two values
+#[inline]
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    a.gcd(&b)
+}
+/// The least common multiple of the two values
+#[inline]
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    a.lcm(&b)
+}
+/// The greatest common divisor of all the values, or zero if there aren't any
+pub fn gcd_slice<T: Integer + Clone>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |result, value| gcd(result, value.clone()))
+}
+/// The least common multiple of all the values, or one if there aren't any
+pub fn lcm_slice<T: Integer + Clone>(values: &[T]) -> T {
+    values.iter().fold(T::one(), |result, value| lcm(result, value.clone()))
+}
+
+/// The extended euclidean algorithm, giving `(g, x, y)`
+/// where `g` is the non-negative gcd and `a * x + b * y == g`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_remainder, mut remainder) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while remainder != 0 {
+        let quotient = old_remainder / remainder;
+        let next_remainder = old_remainder - quotient * remainder;
+        old_remainder = mem::replace(&mut remainder, next_remainder);
+        let next_x = old_x - quotient * x;
+        old_x = mem::replace(&mut x, next_x);
+        let next_y = old_y - quotient * y;
+        old_y = mem::replace(&mut y, next_y);
+    }
+    if old_remainder < 0 {
+        (-old_remainder, -old_x, -old_y)
+    } else {
+        (old_remainder, old_x, old_y)
+    }
+}
+
+/// Solve the system of congruences `x ≡ residues[i] (mod moduli[i])`,
+/// giving the unique solution modulo the product of the moduli.
+///
+/// Returns `None` if the moduli aren't pairwise coprime.
+pub fn chinese_remainder(residues: &[i64], moduli: &[i64]) -> Option<i64> {
+    assert_eq!(residues.len(), moduli.len());
+    let (mut solution, mut product) = (0i128, 1i64);
+    for (&residue, &modulus) in residues.iter().zip(moduli) {
+        assert!(modulus > 0, "Invalid modulus: {}", modulus);
+        let (g, inverse, _) = extended_gcd(product, modulus);
+        if g != 1 { return None }
+        // Adjust the existing solution by a multiple of the product so it also satisfies this congruence
+        let modulus = modulus as i128;
+        let difference = ((residue as i128) - solution) % modulus;
+        let multiple = (difference * (inverse as i128) % modulus + modulus) % modulus;
+        solution += (product as i128) * multiple;
+        product = product.checked_mul(modulus as i64)
+            .unwrap_or_else(|| panic!("Product of moduli overflowed: {:?}", moduli));
+    }
+    Some(solution as i64)
+}
+
+/// The multiplicative inverse of the value modulo the specified value,
+/// or `None` if they aren't coprime.
+///
+/// This uses the extended euclidean algorithm, so the modulus doesn't need to be prime.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    assert_ne!(modulus, 0);
+    let modulus = modulus as i128;
+    let (mut old_remainder, mut remainder) = ((a as i128) % modulus, modulus);
+    let (mut old_coefficient, mut coefficient) = (1i128, 0i128);
+    while remainder != 0 {
+        let quotient = old_remainder / remainder;
+        let next_remainder = old_remainder - quotient * remainder;
+        old_remainder = mem::replace(&mut remainder, next_remainder);
+        let next_coefficient = old_coefficient - quotient * coefficient;
+        old_coefficient = mem::replace(&mut coefficient, next_coefficient);
+    }
+    if old_remainder != 1 { return None }
+    Some((((old_coefficient % modulus) + modulus) % modulus) as u64)
+}
+
+/// Evaluate the power tower `base^(h0^(h1^...))` modulo the specified value.
+///
+/// The exponents are reduced modulo `totient(modulus)` recursively,
+/// using the generalized form of Euler's theorem so the base doesn't need to be coprime.
+pub fn power_tower_mod(base: u64, heights: &[u64], modulus: u64) -> u64 {
+    assert_ne!(modulus, 0);
+    let mut tower = Vec::with_capacity(heights.len() + 1);
+    tower.push(base);
+    tower.extend_from_slice(heights);
+    tower_mod(&tower, modulus)
+}
+fn tower_mod(tower: &[u64], modulus: u64) -> u64 {
+    if modulus == 1 { return 0 }
+    let (&base, exponents) = tower.split_first().unwrap();
+    if exponents.is_empty() { return base % modulus }
+    /*
+     * We know a^b ≡ a^(b mod φ(n) + φ(n)) (mod n) as long as b >= log2(n),
+     * which is always true once the exponent reaches 64.
+     * Smaller exponents have to be used directly.
+     */
+    match capped_tower(exponents, 64) {
+        small if small < 64 => modular_pow(base, small, modulus),
+        _ => {
+            let phi = primes::totient(modulus);
+            let reduced = tower_mod(exponents, phi);
+            // The exponent can overflow if the modulus is close to u64::MAX, so use a^(r + φ) = a^r * a^φ
+            mul_mod(modular_pow(base, reduced, modulus), modular_pow(base, phi, modulus), modulus)
+        }
+    }
+}
+/// Evaluate the tower exactly, saturating once it reaches the cap
+fn capped_tower(tower: &[u64], cap: u64) -> u64 {
+    let (&base, exponents) = match tower.split_first() {
+        Some(split) => split,
+        None => return 1
+    };
+    let exponent = capped_tower(exponents, cap);
+    let mut result = 1u64;
+    for _ in 0..exponent {
+        result = result.saturating_mul(base);
+        if result >= cap { return cap }
+    }
+    result
+}
+
+/// Find the largest square formed by either word of an anagram pair,
+/// where mapping the square's digits onto the letters of the first word
+/// turns the second word into another square.
+///
+/// Each letter must map to a unique digit, and neither square may have a leading zero.
+pub fn max_anagram_square(pair: (&str, &str)) -> Option<u64> {
+    let (first, second) = (pair.0.as_bytes(), pair.1.as_bytes());
+    let length = first.len() as u32;
+    if length != (second.len() as u32) || length == 0 || length > 18 { return None }
+    let lower = 10u64.pow(length - 1);
+    let upper = 10u64.pow(length);
+    let mut root = isqrt(lower - 1) + 1;
+    let mut best = None;
+    while root * root < upper {
+        let square = root * root;
+        root += 1;
+        let digits = Digits::from_value(square);
+        let mut letter_digits = [None; 256];
+        let mut used_digits = [false; 10];
+        let consistent = first.iter().zip(digits.as_slice()).all(|(&letter, &digit)| {
+            match letter_digits[letter as usize] {
+                Some(existing) => existing == digit,
+                None if used_digits[digit as usize] => false,
+                None => {
+                    letter_digits[letter as usize] = Some(digit);
+                    used_digits[digit as usize] = true;
+                    true
+                }
+            }
+        });
+        if !consistent || letter_digits[second[0] as usize] == Some(0) { continue }
+        let mut other = 0u64;
+        for &letter in second {
+            match letter_digits[letter as usize] {
+                Some(digit) => other = other * 10 + (digit as u64),
+                None => return None // not an anagram
+            }
+        }
+        if is_perfect_square(other) {
+            best = best.max(Some(square.max(other)));
+        }
+    }
+    best
+}
+
+/// All the numbers below the limit that are palindromes in both base 10 and base 2.
+///
+/// Only odd numbers can be binary palindromes, since they can't have a trailing zero.
+pub fn double_base_palindromes_below(limit: u64) -> Vec<u64> {
+    (1..limit).step_by(2)
+        .filter(|&value| is_palindrome_radix(value, 10) && is_palindrome_radix(value, 2))
+        .collect()
+}
+
+/// The factorial of the value, which quickly gets too large for a `u64`
+pub fn factorial(n: u64) -> BigUint {
+    let mut result = BigUint::from(1u64);
+    for i in 2..=n {
+        result = result * BigUint::from(i);
     }
     result
 }
 
+/// The factorial of the value, or `None` if it overflows a `u64`
+pub fn factorial_u64(n: u64) -> Option<u64> {
+    (2..=n).fold(Some(1u64), |result, i| result.and_then(|result| result.checked_mul(i)))
+}
+
+/// An infinite iterator over the fibonacci numbers, starting with zero
+pub struct Fibonacci {
+    current: BigUint,
+    next: BigUint
+}
+impl Iterator for Fibonacci {
+    type Item = BigUint;
+
+    #[inline]
+    fn next(&mut self) -> Option<BigUint> {
+        let following = &self.current + &self.next;
+        let next = mem::replace(&mut self.next, following);
+        Some(mem::replace(&mut self.current, next))
+    }
+}
+
+#[inline]
+pub fn fibonacci() -> Fibonacci {
+    Fibonacci {
+        current: BigUint::zero(),
+        next: BigUint::from(1u64)
+    }
+}
+
+/// The next term of the Collatz sequence, which can exceed a `u64` for large values
+#[inline]
+fn collatz_step(n: u128) -> u128 {
+    if n % 2 == 0 {
+        n / 2
+    } else {
+        n.checked_mul(3).and_then(|n| n.checked_add(1))
+            .unwrap_or_else(|| panic!("Collatz sequence overflowed at {}", n))
+    }
+}
+
+/// The number of steps the Collatz sequence starting at `n` takes to reach one
+pub fn collatz_length(n: u64) -> u64 {
+    assert!(n > 0, "Invalid starting value: {}", n);
+    let mut value = n as u128;
+    let mut steps = 0;
+    while value != 1 {
+        value = collatz_step(value);
+        steps += 1;
+    }
+    steps
+}
+
+/// The Collatz lengths of every value up to and including the limit,
+/// reusing the lengths of smaller values once the sequence drops below its start.
+///
+/// The length of zero is meaningless, so it's given as zero.
+pub fn collatz_lengths_up_to(limit: u64) -> Vec<u32> {
+    let mut lengths = vec![0u32; limit as usize + 1];
+    for n in 2..=limit {
+        let mut value = n as u128;
+        let mut steps = 0;
+        while value >= n as u128 {
+            value = collatz_step(value);
+            steps += 1;
+        }
+        lengths[n as usize] = steps + lengths[value as usize];
+    }
+    lengths
+}
+
+/// The sum of the proper divisors of every value below the limit,
+/// found by sieving each divisor into its multiples.
+pub fn proper_divisor_sums(limit: u64) -> Vec<u64> {
+    let mut sums = vec![0u64; limit as usize];
+    for divisor in 1..(limit / 2 + 1) {
+        let mut multiple = divisor * 2;
+        while multiple < limit {
+            sums[multiple as usize] += divisor;
+            multiple += divisor;
+        }
+    }
+    sums
+}
+
+/// How a number compares to the sum of its proper divisors
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NumberKind {
+    /// The sum of the proper divisors is equal to the number
+    Perfect,
+    /// The sum of the proper divisors is greater than the number
+    Abundant,
+    /// The sum of the proper divisors is less than the number
+    Deficient
+}
+
+/// Classify the number using the proper divisor sums from `proper_divisor_sums`
+#[inline]
+pub fn classify_number(n: u64, divisor_sum: &[u64]) -> NumberKind {
+    let sum = divisor_sum[n as usize];
+    if sum == n {
+        NumberKind::Perfect
+    } else if sum > n {
+        NumberKind::Abundant
+    } else {
+        NumberKind::Deficient
+    }
+}
+
+/// Find the amicable pairs `(a, b)` with `a < b < limit`,
+/// where each is the sum of the proper divisors of the other.
+///
+/// Perfect numbers would be paired with themselves, so they're never included.
+pub fn amicable_pairs(limit: u64, divisor_sum: &[u64]) -> Vec<(u64, u64)> {
+    assert!(limit as usize <= divisor_sum.len(), "Not enough divisor sums for {}", limit);
+    let mut result = Vec::new();
+    for a in 1..limit {
+        let b = divisor_sum[a as usize];
+        if a < b && b < limit && divisor_sum[b as usize] == a {
+            result.push((a, b));
+        }
+    }
+    result
+}
+
+lazy_static! {
+    static ref DIGIT_FACTORIALS: [u64; 10] = {
+        let mut table = [1u64; 10];
+        for digit in 1..10 {
+            table[digit] = table[digit - 1] * digit as u64;
+        }
+        table
+    };
+}
+/// The sum of the factorials of the digits, which is the value itself for factorions like `145`
+pub fn digit_factorial_sum(n: u64) -> u64 {
+    let table = &*DIGIT_FACTORIALS;
+    Digits::from_value(n).iter().map(|&digit| table[digit as usize]).sum()
+}
+/// The sum of the digits each raised to the specified power, panicking on overflow
+pub fn digit_power_sum(n: u64, power: u32) -> u64 {
+    Digits::from_value(n).iter()
+        .map(|&digit| ::num::checked_pow(digit as u64, power as usize))
+        .fold(Some(0u64), |sum, term| sum.and_then(|sum| sum.checked_add(term?)))
+        .unwrap_or_else(|| panic!("Digit power sum overflowed for {} with power {}", n, power))
+}
+
+/// The sum of the squares of the digits
+#[inline]
+fn digit_square_sum(n: u64) -> u64 {
+    Digits::from_value(n).iter().map(|&digit| (digit as u64) * (digit as u64)).sum()
+}
+
+/// Check if repeatedly summing the squares of the digits eventually reaches one.
+///
+/// Otherwise the sequence ends up in a cycle (`4 -> 16 -> ... -> 4`),
+/// which is detected using Floyd's algorithm.
+pub fn is_happy(n: u64) -> bool {
+    let (mut slow, mut fast) = (n, n);
+    loop {
+        slow = digit_square_sum(slow);
+        fast = digit_square_sum(digit_square_sum(fast));
+        if fast == 1 { return true }
+        if slow == fast { return slow == 1 }
+    }
+}
+
+/// Find the fractions of `num_digits`-digit numbers where naively cancelling
+/// a digit shared by the numerator and denominator gives the same value, like `49/98 = 4/8`.
+///
+/// Cancelling zeros is considered trivial (like `30/50 = 3/5`) so it's never counted.
+/// Only fractions whose value passes the `keep` predicate are included.
+pub fn digit_cancelling_fractions<F: Fn(Ratio<u64>) -> bool>(num_digits: u32, keep: F) -> Vec<(u64, u64)> {
+    assert!(num_digits >= 2 && num_digits < 20, "Invalid number of digits: {}", num_digits);
+    fn without_digit(digits: &Digits, index: usize) -> u64 {
+        digits.as_slice().iter().enumerate()
+            .filter(|&(i, _)| i != index)
+            .fold(0, |value, (_, &digit)| value * 10 + digit as u64)
+    }
+    let lower = 10u64.pow(num_digits - 1);
+    let upper = 10u64.pow(num_digits);
+    let length = num_digits as usize;
+    let mut result = Vec::new();
+    for numerator in lower..upper {
+        let numerator_digits = Digits::from_value(numerator);
+        for denominator in lower..upper {
+            if !keep(Ratio::new(numerator, denominator)) { continue }
+            let denominator_digits = Digits::from_value(denominator);
+            let cancels = (0..length).any(|i| (0..length).any(|j| {
+                let digit = numerator_digits[i];
+                if digit == 0 || digit != denominator_digits[j] { return false }
+                let reduced_numerator = without_digit(&numerator_digits, i);
+                let reduced_denominator = without_digit(&denominator_digits, j);
+                reduced_denominator != 0 && (reduced_numerator as u128) * (denominator as u128)
+                    == (reduced_denominator as u128) * (numerator as u128)
+            }));
+            if cancels {
+                result.push((numerator, denominator));
+            }
+        }
+    }
+    result
+}
+
+/// The least `k > 0` where `a^k ≡ 1 (mod n)`, or `None` if `a` and `n` aren't coprime.
+///
+/// The order always divides `φ(n)`, so we start there and
+/// divide out each prime factor for as long as the power stays at one.
+pub fn multiplicative_order(a: u64, n: u64) -> Option<u64> {
+    assert_ne!(n, 0, "Invalid modulus");
+    if n == 1 { return Some(1) }
+    if a.gcd(&n) != 1 { return None }
+    let mut order = primes::totient(n);
+    for (prime, _) in primes::factorize(order) {
+        while order % prime == 0 && modular_pow(a, order / prime, n) == 1 {
+            order /= prime;
+        }
+    }
+    Some(order)
+}
+/// The length of the recurring cycle in the decimal expansion of `1/d`,
+/// or zero if the expansion terminates.
+///
+/// Factors of two and five only delay the start of the cycle,
+/// so after removing them this is the multiplicative order of ten.
+pub fn reciprocal_cycle_length(mut d: u64) -> u64 {
+    assert_ne!(d, 0, "Reciprocal of zero");
+    while d % 2 == 0 { d /= 2 }
+    while d % 5 == 0 { d /= 5 }
+    if d == 1 { return 0 }
+    multiplicative_order(10, d).unwrap()
+}
+/// Find the `d < limit` where `1/d` has the longest recurring cycle in its decimal expansion,
+/// or zero if none of them recur.
+///
+/// Only primes need to be checked, since the cycle of a prime `p` can be as long as `p - 1`.
+/// Checking them in descending order lets us stop once that bound can't beat the best so far.
+pub fn longest_reciprocal_cycle_below(limit: u64) -> u64 {
+    if limit <= 3 { return 0 }
+    let (mut best, mut best_length) = (0, 0);
+    for &p in primes::primes(limit).iter().rev() {
+        if p - 1 <= best_length { break }
+        let length = reciprocal_cycle_length(p);
+        if length > best_length {
+            best = p;
+            best_length = length;
+        }
+    }
+    best
+}
+
+/// Compute the floor of the square root exactly, without any floating point.
+///
+/// This uses Newton's method starting from a power of two above the root,
+/// so the estimates decrease monotonically until they reach the floor.
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 { return n }
+    let shift = (64 - n.leading_zeros() + 1) / 2;
+    let mut estimate = 1u64 << shift;
+    loop {
+        let next = (estimate + n / estimate) / 2;
+        if next >= estimate { return estimate }
+        estimate = next;
+    }
+}
+/// Check if the value is the square of some integer
+#[inline]
+pub fn is_perfect_square(n: u64) -> bool {
+    let root = isqrt(n);
+    root * root == n
+}
+
+/// Compute the floor of the `n`th root exactly, without any floating point.
+///
+/// This binary searches for the root, which is always less than `2^(64/n + 1)`.
+pub fn integer_nth_root(value: u64, n: u32) -> u64 {
+    assert!(n > 0, "Invalid root: {}", n);
+    if n == 1 || value < 2 { return value }
+    // Invariant: low^n <= value < high^n
+    let (mut low, mut high) = (1u64, 1u64 << (64 / n + 1));
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        match ::num::checked_pow(middle, n as usize) {
+            Some(power) if power <= value => low = middle,
+            _ => high = middle
+        }
+    }
+    low
+}
+/// Find a base and exponent (of at least two) that give the specified value,
+/// or `None` if it's not a perfect power.
+///
+/// This prefers the largest possible exponent, so `64` gives `(2, 6)` instead of `(8, 2)`.
+/// Zero and one are never considered perfect powers.
+pub fn is_perfect_power(value: u64) -> Option<(u64, u32)> {
+    if value < 2 { return None }
+    let max_exponent = 63 - value.leading_zeros();
+    (2..(max_exponent + 1)).rev()
+        .map(|exponent| (integer_nth_root(value, exponent), exponent))
+        .find(|&(base, exponent)| base.pow(exponent) == value)
+}
+
+/// Check if the value is a triangular number `k(k + 1)/2` for some `k >= 1`
+#[inline]
+pub fn is_triangular(n: u64) -> bool {
+    let approx_index = ((8.0 * n as f64 + 1.0).sqrt() - 1.0) / 2.0;
+    check_figurate_index(n, approx_index, |k| k * (k + 1) / 2)
+}
+/// Check if the value is a pentagonal number `k(3k - 1)/2` for some `k >= 1`
+#[inline]
+pub fn is_pentagonal(n: u64) -> bool {
+    let approx_index = (1.0 + (24.0 * n as f64 + 1.0).sqrt()) / 6.0;
+    check_figurate_index(n, approx_index, |k| k * (3 * k - 1) / 2)
+}
+/// Check if the value is a hexagonal number `k(2k - 1)` for some `k >= 1`
+#[inline]
+pub fn is_hexagonal(n: u64) -> bool {
+    let approx_index = (1.0 + (8.0 * n as f64 + 1.0).sqrt()) / 4.0;
+    check_figurate_index(n, approx_index, |k| k * (2 * k - 1))
+}
+/// Verify the approximate index from the floating point inverse actually gives the value,
+/// since rounding errors could make us wrong near the boundary.
+#[inline]
+fn check_figurate_index<F: Fn(u128) -> u128>(n: u64, approx_index: f64, figurate: F) -> bool {
+    let index = approx_index.round();
+    index >= 1.0 && figurate(index as u128) == (n as u128)
+}
+
+/// An infinite iterator over the triangular numbers, starting with one
+#[inline]
+pub fn triangular_numbers() -> impl Iterator<Item=u64> {
+    (1u64..).map(|k| k * (k + 1) / 2)
+}
+/// An infinite iterator over the pentagonal numbers, starting with one
+#[inline]
+pub fn pentagonal_numbers() -> impl Iterator<Item=u64> {
+    (1u64..).map(|k| k * (3 * k - 1) / 2)
+}
+/// An infinite iterator over the hexagonal numbers, starting with one
+#[inline]
+pub fn hexagonal_numbers() -> impl Iterator<Item=u64> {
+    (1u64..).map(|k| k * (2 * k - 1))
+}
+
 /// Find a reasonable approximation of the first input
 /// where the function returns true.
 pub fn guess_first_match<F, T>(mut func: F) -> T
@@ -147,6 +868,26 @@ pub fn guess_first_match<F, T>(mut func: F) -> T
     guess
 }
 
+/// Find the exact first input where the monotone function returns true,
+/// by binary searching after `guess_first_match` finds an upper bound.
+pub fn first_match<F, T>(mut func: F) -> T
+    where F: FnMut(T) -> bool, T: Ord + ::num::PrimInt + ::std::ops::MulAssign {
+    let mut upper = guess_first_match(&mut func);
+    if upper.is_zero() { return upper }
+    // The previous guess was false (or zero, which was already checked)
+    let two = T::from(2).unwrap();
+    let mut lower = if upper == T::one() { T::zero() } else { upper / two };
+    while upper - lower > T::one() {
+        let middle = lower + (upper - lower) / two;
+        if func(middle) {
+            upper = middle;
+        } else {
+            lower = middle;
+        }
+    }
+    upper
+}
+
 pub unsafe trait ArbitraryBytes {}
 unsafe impl ArbitraryBytes for u64 {}
 unsafe impl ArbitraryBytes for u32 {}
@@ -194,6 +935,424 @@ mod test {
         );
     }
     #[test]
+    fn lazy_product() {
+        assert_eq!(
+            product(&[0, 1], 3),
+            vec![
+                vec![0, 0, 0], vec![0, 0, 1], vec![0, 1, 0], vec![0, 1, 1],
+                vec![1, 0, 0], vec![1, 0, 1], vec![1, 1, 0], vec![1, 1, 1],
+            ]
+        );
+        assert_eq!(product_iter(&[0, 1], 3).collect_vec(), product(&[0, 1], 3));
+        assert_eq!(product_iter(b"abc", 2).count(), 9);
+        assert_eq!(product_iter(&[1, 2], 0).collect_vec(), vec![Vec::<i32>::new()]);
+        assert_eq!(product_iter(&[] as &[i32], 2).count(), 0);
+        // Stopping early doesn't generate the remaining combinations
+        let alphabet = (b'a'..=b'z').collect_vec();
+        assert_eq!(product_iter(&alphabet, 8).nth(27).unwrap(), b"aaaaaabb");
+    }
+    #[test]
+    fn combinations() {
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(52, 5), 2_598_960);
+        assert_eq!(binomial(3, 4), 0);
+        assert_eq!(binomial(67, 33), 14_226_520_737_620_288_370);
+        assert_eq!(
+            combinations_iter(&[1, 2, 3, 4], 2).collect_vec(),
+            vec![vec![1, 2], vec![1, 3], vec![1, 4], vec![2, 3], vec![2, 4], vec![3, 4]]
+        );
+        for &(n, k) in &[(4, 2), (5, 0), (5, 5), (6, 3), (10, 4), (3, 4), (0, 0)] {
+            let items = (0..n).collect_vec();
+            let combinations = combinations_iter(&items, k).collect_vec();
+            assert_eq!(combinations.len() as u64, combinations_count(n as u64, k as u64), "{} choose {}", n, k);
+            if k > 0 {
+                // Itertools doesn't give the single empty combination
+                assert_eq!(combinations, items.iter().cloned().combinations(k).collect_vec());
+            }
+        }
+    }
+    #[test]
+    fn lazy_permutations() {
+        assert_eq!(
+            permutations_iter(vec![0, 1, 2], 3).collect_vec(),
+            permutations(vec![0, 1, 2], 3)
+        );
+        assert_eq!(
+            permutations_iter(vec!['a', 'b', 'c', 'd'], 2).collect_vec(),
+            permutations(vec!['a', 'b', 'c', 'd'], 2)
+        );
+        assert_eq!(permutations_iter(vec![1, 2], 0).collect_vec(), vec![Vec::<i32>::new()]);
+        // Stopping early doesn't generate the remaining permutations
+        let found = permutations_iter((0..20).collect_vec(), 20)
+            .find(|permutation| permutation[18] == 19)
+            .unwrap();
+        assert_eq!(found[..18], (0..18).collect_vec()[..]);
+    }
+    #[test]
+    fn direct_permutation() {
+        assert_eq!(nth_permutation(&[0, 1, 2, 3], 0), Some(vec![0, 1, 2, 3]));
+        assert_eq!(nth_permutation(&[0, 1, 2, 3], 23), Some(vec![3, 2, 1, 0]));
+        assert_eq!(nth_permutation(&[0, 1, 2, 3], 24), None);
+        let all = permutations(vec![0, 1, 2, 3], 4);
+        for (n, permutation) in all.iter().enumerate() {
+            assert_eq!(nth_permutation(&[0, 1, 2, 3], n as u64).as_ref(), Some(permutation));
+        }
+        let digits = (0..10).collect_vec();
+        let millionth = nth_permutation(&digits, 999_999).unwrap();
+        assert_eq!(millionth.iter().join(""), "2783915460");
+        // Too many permutations to count in a u64
+        let values = (0..25).collect_vec();
+        assert_eq!(nth_permutation(&values, 0), Some(values.clone()));
+        assert_eq!(nth_permutation(&values, u64::max_value()).unwrap().len(), 25);
+        assert_eq!(nth_permutation(&[] as &[i32], 0), Some(vec![]));
+    }
+    #[test]
+    fn rank_permutation() {
+        for n in 0..24 {
+            let permutation = nth_permutation(&[0, 1, 2, 3], n).unwrap();
+            assert_eq!(permutation_rank(&permutation), n);
+        }
+        assert_eq!(permutation_rank(&[2, 7, 8, 3, 9, 1, 5, 4, 6, 0]), 999_999);
+        assert_eq!(permutation_rank(&['c', 'b', 'a']), 5);
+        assert_eq!(permutation_rank::<u8>(&[]), 0);
+        let values = (0..20u64).rev().collect_vec();
+        assert_eq!(permutation_rank(&values), factorial_u64(20).unwrap() - 1);
+    }
+    #[test]
+    fn exact_first_match() {
+        assert_eq!(first_match(|n: u64| n * n > 1_000_000), 1001);
+        assert_eq!(guess_first_match(|n: u64| n * n > 1_000_000), 1024);
+        assert_eq!(first_match(|_: u32| true), 0);
+        assert_eq!(first_match(|n: u32| n >= 1), 1);
+        assert_eq!(first_match(|n: u32| n >= 2), 2);
+        assert_eq!(first_match(|n: u32| n >= 3), 3);
+        for target in 0..200u64 {
+            assert_eq!(first_match(|n: u64| n >= target), target);
+        }
+    }
+    #[test]
+    fn test_modular_pow() {
+        assert_eq!(modular_pow(4, 13, 497), 445);
+        assert_eq!(modular_pow(7, 0, 13), 1);
+        assert_eq!(modular_pow(7, 5, 1), 0);
+        // Large enough that multiplying two residues overflows a u64
+        let modulus = 10_000_000_019u64;
+        let expected = BigUint::from(2u64)
+            .modpow(&BigUint::from(1_000_000u64), &BigUint::from(modulus));
+        assert_eq!(modular_pow(2, 1_000_000, modulus), expected.to_u64().unwrap());
+        let modulus = u64::max_value() - 58;
+        let expected = BigUint::from(u64::max_value() - 1)
+            .modpow(&BigUint::from(12345u64), &BigUint::from(modulus));
+        assert_eq!(modular_pow(u64::max_value() - 1, 12345, modulus), expected.to_u64().unwrap());
+    }
+    #[test]
+    fn timer_duration() {
+        let timer = DebugTimer::force_start();
+        ::std::thread::sleep(Duration::from_millis(1));
+        let elapsed = timer.elapsed().unwrap();
+        assert!(elapsed >= Duration::from_millis(1));
+        let total = timer.finish_returning(&"Slept").unwrap();
+        assert!(total >= elapsed);
+        let disabled = DebugTimer { start: None };
+        assert_eq!(disabled.elapsed(), None);
+        assert_eq!(disabled.finish_returning(&"Nothing"), None);
+    }
+    #[test]
+    fn collatz() {
+        assert_eq!(collatz_length(1), 0);
+        assert_eq!(collatz_length(2), 1);
+        assert_eq!(collatz_length(13), 9);
+        assert_eq!(collatz_length(27), 111);
+        // The first step overflows a u64
+        assert!(collatz_length(u64::max_value()) > 0);
+        let lengths = collatz_lengths_up_to(999_999);
+        // The limit itself is included
+        assert_eq!(lengths.len(), 1_000_000);
+        assert_eq!(collatz_lengths_up_to(27).last(), Some(&(collatz_length(27) as u32)));
+        assert_eq!(collatz_lengths_up_to(0), vec![0]);
+        assert_eq!(collatz_lengths_up_to(2), vec![0, 0, 1]);
+        for n in (1..1000).chain(999_000..1_000_000) {
+            assert_eq!(lengths[n] as u64, collatz_length(n as u64), "Wrong length for {}", n);
+        }
+        let longest = (1..lengths.len()).max_by_key(|&n| lengths[n]).unwrap();
+        assert_eq!(longest, 837_799);
+        assert_eq!(lengths[longest], 524);
+    }
+    #[test]
+    fn classify_divisor_sums() {
+        let sums = proper_divisor_sums(10_000);
+        assert_eq!(&sums[..13], &[0, 0, 1, 1, 3, 1, 6, 1, 7, 4, 8, 1, 16]);
+        assert_eq!(classify_number(6, &sums), NumberKind::Perfect);
+        assert_eq!(classify_number(28, &sums), NumberKind::Perfect);
+        assert_eq!(classify_number(8128, &sums), NumberKind::Perfect);
+        assert_eq!(classify_number(12, &sums), NumberKind::Abundant);
+        assert_eq!(classify_number(13, &sums), NumberKind::Deficient);
+        let pairs = amicable_pairs(10_000, &sums);
+        assert_eq!(pairs, vec![(220, 284), (1184, 1210), (2620, 2924), (5020, 5564), (6232, 6368)]);
+        let total: u64 = pairs.iter().map(|&(a, b)| a + b).sum();
+        assert_eq!(total, 31626);
+        // Both members of the pair must be below the limit
+        assert_eq!(amicable_pairs(284, &sums), vec![]);
+    }
+    #[test]
+    fn digit_chains() {
+        for &factorion in &[1, 2, 145, 40585] {
+            assert_eq!(digit_factorial_sum(factorion), factorion);
+        }
+        assert_eq!(digit_factorial_sum(169), 363_601);
+        assert_eq!(digit_factorial_sum(0), 1);
+        let factorions = (1..100_000).filter(|&n| digit_factorial_sum(n) == n).collect_vec();
+        assert_eq!(factorions, vec![1, 2, 145, 40585]);
+        assert_eq!(digit_power_sum(1634, 4), 1634);
+        assert_eq!(digit_power_sum(153, 3), 153);
+        assert_eq!(digit_power_sum(12, 2), 5);
+        assert_eq!(digit_power_sum(0, 0), 1);
+    }
+    #[test]
+    fn happy_numbers() {
+        assert!(is_happy(1));
+        assert!(is_happy(7));
+        assert!(is_happy(19));
+        assert!(!is_happy(4));
+        assert!(!is_happy(2));
+        assert!(!is_happy(0));
+        let happy = (1..=1000).filter(|&n| is_happy(n)).collect_vec();
+        assert_eq!(&happy[..8], &[1, 7, 10, 13, 19, 23, 28, 31]);
+        assert_eq!(happy.len(), 143);
+    }
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(17u64, 5), 1);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm_slice(&[2, 3, 4]), 12);
+        assert_eq!(lcm_slice(&(1..=20u64).collect_vec()), 232_792_560);
+        assert_eq!(gcd_slice(&[12, 18, 27]), 3);
+        assert_eq!(gcd_slice::<u32>(&[]), 0);
+        assert_eq!(lcm_slice::<u32>(&[]), 1);
+        assert_eq!(gcd(BigInt::from(48), BigInt::from(-36)), BigInt::from(12));
+    }
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(extended_gcd(240, 46).0, 2);
+        assert_eq!(extended_gcd(7, 0), (7, 1, 0));
+        assert_eq!(extended_gcd(-7, 0), (7, -1, 0));
+        assert_eq!(extended_gcd(0, 0).0, 0);
+        let pairs = [(240, 46), (46, 240), (-240, 46), (240, -46), (-240, -46), (0, 5), (17, 5), (1071, 462)];
+        for &(a, b) in &pairs {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, ::num::integer::gcd(a, b), "Invalid gcd for {} and {}", a, b);
+            assert_eq!(a * x + b * y, g, "Invalid coefficients for {} and {}", a, b);
+        }
+    }
+    #[test]
+    fn test_chinese_remainder() {
+        assert_eq!(chinese_remainder(&[2, 3, 2], &[3, 5, 7]), Some(23));
+        assert_eq!(chinese_remainder(&[], &[]), Some(0));
+        assert_eq!(chinese_remainder(&[-1], &[5]), Some(4));
+        assert_eq!(chinese_remainder(&[1, 2], &[2, 4]), None);
+        assert_eq!(chinese_remainder(&[1, 3], &[2, 4]), None);
+        let moduli = [7, 11, 13, 17, 19];
+        for x in &[0i64, 1, 1000, 123_456, 323_322] {
+            let residues = moduli.iter().map(|&m| x % m).collect::<Vec<_>>();
+            assert_eq!(chinese_remainder(&residues, &moduli), Some(*x));
+        }
+    }
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        let coprime = [(3, 10), (7, 40), (17, 3120), (35, 64), (1_000_000_006, 1_000_000_007)];
+        for &(a, modulus) in &coprime {
+            let inverse = mod_inverse(a, modulus).unwrap();
+            assert!(inverse < modulus);
+            assert_eq!(mul_mod(a, inverse, modulus), 1, "Invalid inverse of {} mod {}", a, modulus);
+        }
+    }
+    #[test]
+    fn test_power_tower_mod() {
+        fn naive(base: u64, exponent: usize, modulus: u64) -> u64 {
+            (::num::pow::pow(BigUint::from(base), exponent) % BigUint::from(modulus))
+                .to_u64().unwrap()
+        }
+        assert_eq!(power_tower_mod(2, &[3, 2], 1000), 512);
+        assert_eq!(power_tower_mod(2, &[2, 2, 2], 1000), 65536 % 1000);
+        assert_eq!(power_tower_mod(7, &[3, 4], 100), naive(7, 81, 100));
+        assert_eq!(power_tower_mod(3, &[3, 3], 1000), naive(3, 27, 1000));
+        // The base and modulus don't need to be coprime
+        assert_eq!(power_tower_mod(12, &[2, 5], 1000), naive(12, 32, 1000));
+        assert_eq!(power_tower_mod(2, &[3, 2, 2], 1_000_000), naive(2, 81, 1_000_000));
+        assert_eq!(power_tower_mod(5, &[0], 7), 1);
+        assert_eq!(power_tower_mod(5, &[], 7), 5);
+        // The totient of this modulus is above 2^63,
+        // and adding it to the reduced exponent (3^54 mod φ) would overflow
+        let modulus = 13_963_646_602_082_100_175;
+        assert_eq!(power_tower_mod(2, &[100], modulus), naive(2, 100, modulus));
+        assert_eq!(power_tower_mod(2, &[3, 54], modulus), 6_678_541_893_148_907_937);
+    }
+    #[test]
+    fn test_max_anagram_square() {
+        // 1296 and 9216
+        assert_eq!(max_anagram_square(("CARE", "RACE")), Some(9216));
+        assert_eq!(max_anagram_square(("RACE", "CARE")), Some(9216));
+        assert_eq!(max_anagram_square(("CARE", "BEAR")), None);
+        assert_eq!(max_anagram_square(("CARE", "CAR")), None);
+    }
+    #[test]
+    fn test_double_base_palindromes() {
+        assert_eq!(double_base_palindromes_below(10), vec![1, 3, 5, 7, 9]);
+        assert!(double_base_palindromes_below(1000).contains(&585));
+        assert_eq!(double_base_palindromes_below(1_000_000).iter().sum::<u64>(), 872187);
+    }
+    #[test]
+    fn test_factorial() {
+        assert_eq!(factorial(0), BigUint::from(1u64));
+        assert_eq!(factorial(1), BigUint::from(1u64));
+        assert_eq!(factorial(10), BigUint::from(3_628_800u64));
+        assert_eq!(factorial(100).count_decimal_digits(), 158);
+        assert_eq!(factorial_u64(0), Some(1));
+        assert_eq!(factorial_u64(10), Some(3_628_800));
+        assert_eq!(factorial_u64(20), Some(2_432_902_008_176_640_000));
+        assert_eq!(factorial_u64(21), None);
+        assert_eq!(factorial(20).to_u64(), factorial_u64(20));
+    }
+    #[test]
+    fn test_fibonacci() {
+        assert_eq!(
+            fibonacci().take(8).collect::<Vec<_>>(),
+            [0u64, 1, 1, 2, 3, 5, 8, 13].iter().map(|&i| BigUint::from(i)).collect::<Vec<_>>()
+        );
+        assert_eq!(fibonacci().nth(10), Some(55u64.into()));
+        let threshold = ::num::pow::pow(BigUint::from(10u64), 999);
+        assert_eq!(fibonacci().position(|value| value >= threshold), Some(4782));
+    }
+    #[test]
+    fn test_digit_cancelling_fractions() {
+        let one = Ratio::from_integer(1);
+        let curious = digit_cancelling_fractions(2, |value| value < one);
+        assert_eq!(curious, vec![(16, 64), (19, 95), (26, 65), (49, 98)]);
+        let product = curious.iter()
+            .fold(one, |product, &(numerator, denominator)| product * Ratio::new(numerator, denominator));
+        assert_eq!(product, Ratio::new(1, 100));
+        // Everything is equal to itself, except when that would cancel a zero
+        let trivial = digit_cancelling_fractions(2, |value| value == one);
+        assert_eq!(trivial.len(), 81);
+        assert!(trivial.iter().all(|&(numerator, denominator)| numerator == denominator && numerator % 10 != 0));
+    }
+    #[test]
+    fn test_multiplicative_order() {
+        assert_eq!(multiplicative_order(10, 7), Some(6));
+        assert_eq!(multiplicative_order(2, 7), Some(3));
+        assert_eq!(multiplicative_order(1, 7), Some(1));
+        assert_eq!(multiplicative_order(5, 1), Some(1));
+        assert_eq!(multiplicative_order(10, 12), None);
+        assert_eq!(multiplicative_order(0, 5), None);
+        // Compare against brute force
+        for n in 2..200u64 {
+            for a in 1..n {
+                let expected = if a.gcd(&n) == 1 {
+                    (1..n).find(|&k| modular_pow(a, k, n) == 1)
+                } else {
+                    None
+                };
+                assert_eq!(multiplicative_order(a, n), expected, "ord_{}({})", n, a);
+            }
+        }
+    }
+    #[test]
+    fn test_reciprocal_cycle_length() {
+        let expected = [0, 0, 1, 0, 0, 1, 6, 0, 1, 0];
+        for (d, &length) in (1..11).zip(expected.iter()) {
+            assert_eq!(reciprocal_cycle_length(d), length, "1/{}", d);
+        }
+        assert_eq!(reciprocal_cycle_length(7 * 16 * 125), 6);
+        assert_eq!(reciprocal_cycle_length(983), 982);
+    }
+    #[test]
+    fn test_longest_reciprocal_cycle() {
+        assert_eq!(longest_reciprocal_cycle_below(3), 0);
+        assert_eq!(longest_reciprocal_cycle_below(4), 3);
+        assert_eq!(longest_reciprocal_cycle_below(10), 7);
+        assert_eq!(longest_reciprocal_cycle_below(1000), 983);
+    }
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(17), 4);
+        for root in 1..2000u64 {
+            let square = root * root;
+            assert_eq!(isqrt(square - 1), root - 1);
+            assert_eq!(isqrt(square), root);
+            assert_eq!(isqrt(square + 1), root);
+            assert!(is_perfect_square(square));
+            assert!(!is_perfect_square(square + 1));
+        }
+        // Floating point rounds this up to the next integer
+        let max_root = u32::max_value() as u64;
+        assert_eq!(isqrt(max_root * max_root - 1), max_root - 1);
+        assert_eq!(isqrt(max_root * max_root), max_root);
+        assert_eq!(isqrt(u64::max_value()), max_root);
+        assert!(is_perfect_square(max_root * max_root));
+        assert!(!is_perfect_square(u64::max_value()));
+    }
+    #[test]
+    fn test_integer_nth_root() {
+        assert_eq!(integer_nth_root(1000, 3), 10);
+        assert_eq!(integer_nth_root(1001, 3), 10);
+        assert_eq!(integer_nth_root(999, 3), 9);
+        assert_eq!(integer_nth_root(0, 5), 0);
+        assert_eq!(integer_nth_root(1, 5), 1);
+        assert_eq!(integer_nth_root(12345, 1), 12345);
+        assert_eq!(integer_nth_root(u64::max_value(), 2), isqrt(u64::max_value()));
+        assert_eq!(integer_nth_root(u64::max_value(), 3), 2_642_245);
+        assert_eq!(integer_nth_root(u64::max_value(), 64), 1);
+        assert_eq!(integer_nth_root(1 << 63, 63), 2);
+    }
+    #[test]
+    fn test_is_perfect_power() {
+        assert_eq!(is_perfect_power(81), Some((3, 4)));
+        assert_eq!(is_perfect_power(64), Some((2, 6)));
+        assert_eq!(is_perfect_power(1 << 63), Some((2, 63)));
+        assert_eq!(is_perfect_power(3u64.pow(40)), Some((3, 40)));
+        assert_eq!(is_perfect_power(1_000_000_007), None);
+        assert_eq!(is_perfect_power(72), None);
+        assert_eq!(is_perfect_power(1), None);
+        let powers = (2..1000u64).filter(|&n| is_perfect_power(n).is_some()).collect_vec();
+        let expected = (2..1000u64).filter(|&n| {
+            (2..32u64).any(|base| (2..10).any(|exponent| base.pow(exponent) == n))
+        }).collect_vec();
+        assert_eq!(powers, expected);
+    }
+    #[test]
+    fn test_figurate_numbers() {
+        assert_eq!(triangular_numbers().take(5).collect_vec(), vec![1, 3, 6, 10, 15]);
+        assert_eq!(pentagonal_numbers().take(5).collect_vec(), vec![1, 5, 12, 22, 35]);
+        assert_eq!(hexagonal_numbers().take(5).collect_vec(), vec![1, 6, 15, 28, 45]);
+        for &n in &[40755, 1_533_776_805] {
+            assert!(is_triangular(n) && is_pentagonal(n) && is_hexagonal(n), "{} isn't T, P, and H", n);
+        }
+        assert!(!is_triangular(0) && !is_pentagonal(0) && !is_hexagonal(0));
+        let limit = 10_000;
+        let check = |predicate: fn(u64) -> bool, expected: Vec<u64>| {
+            assert_eq!((0..limit).filter(|&n| predicate(n)).collect_vec(), expected);
+        };
+        check(is_triangular, triangular_numbers().take_while(|&n| n < limit).collect());
+        check(is_pentagonal, pentagonal_numbers().take_while(|&n| n < limit).collect());
+        check(is_hexagonal, hexagonal_numbers().take_while(|&n| n < limit).collect());
+        // Large enough that floating point loses precision
+        let k = 3_000_000_000u64;
+        let triangle = k * (k + 1) / 2;
+        assert!(is_triangular(triangle));
+        assert!(!is_triangular(triangle + 1));
+        assert!(!is_triangular(triangle - 1));
+    }
+    #[test]
     fn test_product() {
         assert_eq!(
             product(&[0, 1], 3),
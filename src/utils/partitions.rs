@@ -0,0 +1,133 @@
+//! Counting the ways to write an amount as a sum of parts
+use num::{BigUint, Zero, One};
+
+use super::primes::primes;
+
+/// Count the ways to make the amount from any number of the specified coins,
+/// where the order of the coins doesn't matter.
+///
+/// This overflows for large amounts, so use `count_partitions_into` for those.
+pub fn count_coin_combinations(amount: usize, coins: &[usize]) -> u64 {
+    let mut ways = vec![0u64; amount + 1];
+    ways[0] = 1;
+    for &coin in coins {
+        assert_ne!(coin, 0, "Invalid coin");
+        for total in coin..(amount + 1) {
+            ways[total] += ways[total - coin];
+        }
+    }
+    ways[amount]
+}
+
+/// Count the ways to write the amount as a sum of the specified parts,
+/// where the order of the parts doesn't matter.
+///
+/// This is the same as `count_coin_combinations`, except it can never overflow.
+pub fn count_partitions_into(amount: usize, parts: &[usize]) -> BigUint {
+    let mut ways = vec![BigUint::zero(); amount + 1];
+    ways[0] = BigUint::one();
+    for &part in parts {
+        assert_ne!(part, 0, "Invalid part");
+        for total in part..(amount + 1) {
+            let previous = ways[total - part].clone();
+            ways[total] += previous;
+        }
+    }
+    ways.swap_remove(amount)
+}
+
+/// Count the ways to write the value as a sum of primes
+pub fn prime_partition_count(n: usize) -> BigUint {
+    // NOTE: `primes` requires a limit of at least two
+    let parts = if n >= 2 {
+        primes(n as u64 + 1).into_iter().map(|p| p as usize).collect()
+    } else {
+        Vec::new()
+    };
+    count_partitions_into(n, &parts)
+}
+
+/// Find the least `n` where the number of unrestricted partitions `p(n)` is divisible by the divisor.
+///
+/// This uses the recurrence from the pentagonal number theorem,
+/// with the partition counts taken modulo the divisor so they always fit in a `u64`.
+pub fn first_partition_divisible_by(divisor: u64) -> usize {
+    assert!(divisor > 0 && divisor <= u64::max_value() / 2, "Invalid divisor: {}", divisor);
+    let mut partitions = vec![1 % divisor];
+    while partitions[partitions.len() - 1] != 0 {
+        let n = partitions.len();
+        let mut total = 0;
+        for k in 1.. {
+            // The generalized pentagonal numbers for k and -k
+            let first = k * (3 * k - 1) / 2;
+            if first > n { break }
+            let mut term = partitions[n - first];
+            let second = k * (3 * k + 1) / 2;
+            if second <= n {
+                term = (term + partitions[n - second]) % divisor;
+            }
+            total = if k % 2 == 1 {
+                (total + term) % divisor
+            } else {
+                (total + divisor - term) % divisor
+            };
+        }
+        partitions.push(total);
+    }
+    partitions.len() - 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const BRITISH_COINS: [usize; 8] = [1, 2, 5, 10, 20, 50, 100, 200];
+    #[test]
+    fn coin_combinations() {
+        assert_eq!(count_coin_combinations(0, &BRITISH_COINS), 1);
+        assert_eq!(count_coin_combinations(5, &BRITISH_COINS), 4);
+        assert_eq!(count_coin_combinations(200, &BRITISH_COINS), 73682);
+        assert_eq!(count_coin_combinations(3, &[2]), 0);
+    }
+    #[test]
+    fn partitions_into() {
+        for amount in 0..300 {
+            assert_eq!(
+                count_partitions_into(amount, &BRITISH_COINS),
+                BigUint::from(count_coin_combinations(amount, &BRITISH_COINS))
+            );
+        }
+        let parts = (1..100).collect::<Vec<usize>>();
+        assert_eq!(count_partitions_into(100, &parts), BigUint::from(190_569_291u64));
+        // Far too many partitions to fit in a u64
+        let parts = (1..1001).collect::<Vec<usize>>();
+        let partitions = count_partitions_into(1000, &parts);
+        assert_eq!(partitions.to_string(), "24061467864032622473692149727991");
+    }
+    #[test]
+    fn prime_partitions() {
+        assert_eq!(prime_partition_count(10), BigUint::from(5u32));
+        assert_eq!(prime_partition_count(2), BigUint::one());
+        assert_eq!(prime_partition_count(1), BigUint::zero());
+        let target = BigUint::from(5000u32);
+        let first = (0..).find(|&n| prime_partition_count(n) > target).unwrap();
+        assert_eq!(first, 71);
+    }
+    #[test]
+    fn partition_divisibility() {
+        assert_eq!(first_partition_divisible_by(1), 0);
+        assert_eq!(first_partition_divisible_by(7), 5);
+        let parts = (1..201).collect::<Vec<usize>>();
+        let partitions = (0..201).map(|n| count_partitions_into(n, &parts)).collect::<Vec<_>>();
+        for divisor in 2..50u64 {
+            let expected = partitions.iter()
+                .position(|count| (count % BigUint::from(divisor)).is_zero())
+                .unwrap();
+            assert_eq!(first_partition_divisible_by(divisor), expected, "Divisor {}", divisor);
+        }
+    }
+    #[test]
+    #[ignore]
+    fn partition_divisible_by_million() {
+        assert_eq!(first_partition_divisible_by(1_000_000), 55374);
+    }
+}
@@ -1,7 +1,9 @@
 //! An implementation of sieve of Eratosthenes
 use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use failure::Error;
 
-use super::DebugTimer;
+use super::{DebugTimer, isqrt};
 
 /// Make a bitset of all primes less than the specified value.
 ///
@@ -10,9 +12,17 @@ use super::DebugTimer;
 pub fn prime_set(limit: u64) -> FixedBitSet {
     assert!(limit <= (usize::max_value() as u64));
     let timer = DebugTimer::start();
+    #[cfg(feature = "rayon")]
+    let is_prime = parallel_prime_set(limit);
+    #[cfg(not(feature = "rayon"))]
+    let is_prime = serial_prime_set(limit);
+    timer.finish_with(|| format!("Computed prime set of {}", limit));
+    is_prime
+}
+fn serial_prime_set(limit: u64) -> FixedBitSet {
     let mut is_prime = FixedBitSet::with_capacity(limit as usize);
     is_prime.set_range(2.., true);
-    for i in 2..((limit as f64).sqrt().ceil() as usize) {
+    for i in 2..(isqrt(limit) as usize + 1) {
         if is_prime[i] {
             let mut j = i * i;
             while j < (limit as usize) {
@@ -21,17 +31,211 @@ pub fn prime_set(limit: u64) -> FixedBitSet {
             }
         }
     }
-    timer.finish_with(|| format!("Computed prime set of {}", limit));
     is_prime
 }
 
+/// The number of blocks each thread sieves at once, which is 128 KiB of bits
+#[cfg(feature = "rayon")]
+const PARALLEL_SEGMENT_BLOCKS: usize = 1 << 12;
+
+/// Sieve the bitset in parallel segments.
+///
+/// The base primes below the square root are computed sequentially,
+/// then each thread clears their multiples from its own chunk of the bitset.
+#[cfg(feature = "rayon")]
+fn parallel_prime_set(limit: u64) -> FixedBitSet {
+    use rayon::prelude::*;
+    const SEGMENT_BITS: usize = PARALLEL_SEGMENT_BLOCKS * 32;
+    if limit < (SEGMENT_BITS as u64) {
+        // Not worth the overhead
+        return serial_prime_set(limit)
+    }
+    let limit = limit as usize;
+    let base_primes = serial_prime_set(isqrt(limit as u64) + 1)
+        .ones().collect::<Vec<usize>>();
+    let mut is_prime = FixedBitSet::with_capacity(limit);
+    is_prime.set_range(2.., true);
+    is_prime.as_mut_slice().par_chunks_mut(PARALLEL_SEGMENT_BLOCKS)
+        .enumerate()
+        .for_each(|(segment, blocks)| {
+            let start = segment * SEGMENT_BITS;
+            let end = (start + blocks.len() * 32).min(limit);
+            for &prime in &base_primes {
+                // Start at the first multiple inside the segment, but never below the square
+                let mut j = (prime * prime).max((start + prime - 1) / prime * prime);
+                while j < end {
+                    let offset = j - start;
+                    blocks[offset / 32] &= !(1 << (offset % 32));
+                    j += prime;
+                }
+            }
+        });
+    is_prime
+}
+
+/// Make a bitset of all odd primes less than the specified value,
+/// where bit `i` represents the odd number `2 * i + 1`.
+///
+/// This only uses half the memory of `prime_set`, since even numbers are never stored.
+/// Use `is_set` to check a value, which also handles two.
+pub fn odd_prime_set(limit: u64) -> FixedBitSet {
+    assert!(limit <= (usize::max_value() as u64));
+    let timer = DebugTimer::start();
+    let limit = limit as usize;
+    let mut is_prime = FixedBitSet::with_capacity(limit / 2);
+    if limit > 3 {
+        // Everything except one starts out prime
+        is_prime.set_range(1.., true);
+    }
+    let mut p = 3;
+    while p * p < limit {
+        if is_prime[p / 2] {
+            let mut j = p * p;
+            while j < limit {
+                is_prime.set(j / 2, false);
+                j += 2 * p;
+            }
+        }
+        p += 2;
+    }
+    timer.finish_with(|| format!("Computed odd prime set of {}", limit));
+    is_prime
+}
+
+/// Check if the value is prime according to a bitset from `odd_prime_set`.
+///
+/// Two is special-cased, since the set only stores odd numbers.
+/// Panics if the value is beyond the limit of the set,
+/// which covers every value up to twice its length (including any value below its limit).
+#[inline]
+pub fn is_set(odd_primes: &FixedBitSet, n: u64) -> bool {
+    assert!(n <= 2 * odd_primes.len() as u64, "{} is beyond the limit of the set", n);
+    if n == 2 {
+        true
+    } else if n % 2 == 0 {
+        false
+    } else {
+        odd_primes.contains((n / 2) as usize)
+    }
+}
+
+/// Make a bitset of all primes in the range `start..end`,
+/// where bit `i` represents the value `start + i`.
+///
+/// This is a segmented sieve, so it only needs memory proportional
+/// to the length of the range (and its square root).
+pub fn prime_set_range(start: u64, end: u64) -> FixedBitSet {
+    assert!(start <= end, "Invalid range {}..{}", start, end);
+    assert!(end - start <= (usize::max_value() as u64));
+    let timer = DebugTimer::start();
+    let mut is_prime = FixedBitSet::with_capacity((end - start) as usize);
+    is_prime.set_range(.., true);
+    // Zero and one aren't prime
+    for n in start..end.min(2) {
+        is_prime.set((n - start) as usize, false);
+    }
+    // NOTE: `prime_set` requires a limit of at least two
+    for p in prime_set(isqrt(end) + 2).ones() {
+        let p = p as u64;
+        // Skip the prime itself, since it might be in the range
+        let mut multiple = (p * p).max((start + p - 1) / p * p);
+        while multiple < end {
+            is_prime.set((multiple - start) as usize, false);
+            multiple += p;
+        }
+    }
+    timer.finish_with(|| format!("Computed prime set of {}..{}", start, end));
+    is_prime
+}
+
+/// The number of values `sieve_consistency_report` checks against `is_prime`
+const CONSISTENCY_SAMPLES: u64 = 10_000;
+
+/// Check that all the sieves agree on every value below the limit,
+/// and that they match `is_prime` on a sample of the values.
+///
+/// Returns an error describing the first mismatch found.
+pub fn sieve_consistency_report(limit: u64) -> Result<(), Error> {
+    let full = prime_set(limit);
+    let odds = odd_prime_set(limit);
+    let range = prime_set_range(0, limit);
+    ensure!(full.len() as u64 == limit, "prime_set({}) has length {}", limit, full.len());
+    ensure!(range.len() as u64 == limit, "prime_set_range(0, {}) has length {}", limit, range.len());
+    for n in 0..limit {
+        let expected = full.contains(n as usize);
+        ensure!(
+            is_set(&odds, n) == expected,
+            "odd_prime_set disagrees with prime_set on {}: expected {}", n, expected
+        );
+        ensure!(
+            range.contains(n as usize) == expected,
+            "prime_set_range disagrees with prime_set on {}: expected {}", n, expected
+        );
+    }
+    // An odd stride, so we don't only check even numbers
+    let stride = (limit / CONSISTENCY_SAMPLES) | 1;
+    let mut n = 0;
+    while n < limit {
+        let expected = full.contains(n as usize);
+        ensure!(
+            is_prime(n) == expected,
+            "is_prime disagrees with prime_set on {}: expected {}", n, expected
+        );
+        n += stride;
+    }
+    Ok(())
+}
+
 /// List of all primes less than the specified value.
 ///
 /// Internally this is just a simple wrapper around `prime_set`.
 pub fn primes(limit: u64) -> Vec<u64> {
+    // NOTE: `prime_set` requires a limit of at least two
+    if limit < 2 { return Vec::new() }
     prime_set(limit).ones().map(|i| i as u64).collect()
 }
 
+/// All pairs of twin primes `(p, p + 2)` where both are less than the specified value.
+///
+/// Since pairs can overlap, 5 is part of both `(3, 5)` and `(5, 7)`.
+pub fn twin_primes(limit: u64) -> impl Iterator<Item=(u64, u64)> {
+    // NOTE: `prime_set` requires a limit of at least two, and there aren't any pairs below five
+    let set = if limit < 5 { FixedBitSet::with_capacity(0) } else { prime_set(limit) };
+    IntoOnes::new(set)
+        .map(|i| i as u64)
+        .tuple_windows()
+        .filter(|&(first, second)| second - first == 2)
+}
+
+/// Iterates over the set bits like `FixedBitSet::ones`, but owns the set so it can be returned
+struct IntoOnes {
+    set: FixedBitSet,
+    block_index: usize,
+    block: u32
+}
+impl IntoOnes {
+    #[inline]
+    fn new(set: FixedBitSet) -> IntoOnes {
+        let block = set.as_slice().first().cloned().unwrap_or(0);
+        IntoOnes { set, block_index: 0, block }
+    }
+}
+impl Iterator for IntoOnes {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.block == 0 {
+            self.block_index += 1;
+            self.block = *self.set.as_slice().get(self.block_index)?;
+        }
+        let bit = self.block.trailing_zeros() as usize;
+        // Clear the lowest set bit
+        self.block &= self.block - 1;
+        Some(self.block_index * 32 + bit)
+    }
+}
+
 
 
 /// Tests if a value is prime
@@ -44,7 +248,7 @@ pub fn is_prime(value: u64) -> bool {
         let mut y = 0;
         debug_assert_ne!(s, 0);
         while s != 0 {
-            y = (x * x) % n;
+            y = ::utils::mul_mod(x, x, n);
             if y == 1 && x != 1 && x != n-1 {
                 return false;
             }
@@ -71,6 +275,312 @@ pub fn is_prime(value: u64) -> bool {
         .all(|&a| witness(value, s, d, a))
 }
 
+/// The gap between each prime and the next one, for all consecutive primes less than the limit
+pub fn prime_gaps(limit: u64) -> impl Iterator<Item=(u64, u64)> {
+    primes(limit).into_iter()
+        .tuple_windows()
+        .map(|(prime, next)| (prime, next - prime))
+}
+
+/// The primes whose gap to the next prime is larger than all previous gaps,
+/// paired with the size of the gap.
+pub fn record_prime_gaps(limit: u64) -> Vec<(u64, u64)> {
+    let mut largest = 0;
+    prime_gaps(limit)
+        .filter(|&(_, gap)| {
+            let record = gap > largest;
+            largest = largest.max(gap);
+            record
+        })
+        .collect()
+}
+
+/// Every prime less than the limit that's a sum of at least `min_run` consecutive primes,
+/// paired with the length of the run.
+///
+/// A prime may appear multiple times if it's the sum of several different runs.
+/// The results are sorted by prime, then by the run length.
+pub fn consecutive_prime_sum_runs(limit: u64, min_run: u32) -> Vec<(u64, u32)> {
+    // NOTE: `prime_set` requires a limit of at least two
+    if limit < 2 { return Vec::new() }
+    let is_prime = prime_set(limit);
+    let primes = is_prime.ones().map(|i| i as u64).collect::<Vec<_>>();
+    let mut runs = Vec::new();
+    for start in 0..primes.len() {
+        let mut sum = 0;
+        for (index, &prime) in primes[start..].iter().enumerate() {
+            sum += prime;
+            if sum >= limit { break }
+            let length = (index + 1) as u32;
+            if length >= min_run && is_prime.contains(sum as usize) {
+                runs.push((sum, length));
+            }
+        }
+    }
+    runs.sort();
+    runs
+}
+
+/// Make a bitset of all the square-free numbers less than the specified value,
+/// which are the numbers without any repeated prime factors.
+///
+/// This sieves out the multiples of every prime square,
+/// so zero is never considered square-free.
+pub fn squarefree_sieve(limit: usize) -> FixedBitSet {
+    let timer = DebugTimer::start();
+    let mut squarefree = FixedBitSet::with_capacity(limit);
+    if limit > 0 {
+        squarefree.set_range(1.., true);
+    }
+    // NOTE: We need all primes up to the square root, and `prime_set` requires a limit of at least two
+    let root = isqrt(limit as u64) + 2;
+    for prime in prime_set(root).ones() {
+        let square = prime * prime;
+        let mut multiple = square;
+        while multiple < limit {
+            squarefree.set(multiple, false);
+            multiple += square;
+        }
+    }
+    timer.finish_with(|| format!("Computed square-free set of {}", limit));
+    squarefree
+}
+
+/// Count the square-free numbers less than the specified value
+#[inline]
+pub fn count_squarefree(limit: usize) -> usize {
+    squarefree_sieve(limit).count_ones(..)
+}
+
+/// The smallest prime strictly greater than the value
+pub fn next_prime(n: u64) -> u64 {
+    if n < 2 { return 2 }
+    // Start at the next odd number, since there are no more even primes
+    let mut candidate = if n % 2 == 0 { n + 1 } else { n + 2 };
+    while !is_prime(candidate) {
+        candidate += 2;
+    }
+    candidate
+}
+
+/// The largest prime strictly less than the value,
+/// or `None` if there isn't one.
+pub fn prev_prime(n: u64) -> Option<u64> {
+    match n {
+        0...2 => None,
+        3 => Some(2),
+        _ => {
+            let mut candidate = if n % 2 == 0 { n - 1 } else { n - 2 };
+            while !is_prime(candidate) {
+                candidate -= 2;
+            }
+            Some(candidate)
+        }
+    }
+}
+
+/// Factorize the value into its prime factors and their multiplicities,
+/// ordered from smallest to largest.
+///
+/// This is just simple trial division.
+pub fn factorize(mut value: u64) -> Vec<(u64, u32)> {
+    assert_ne!(value, 0);
+    let mut factors = Vec::new();
+    let mut divisor = 2;
+    // Avoid squaring the divisor, which would overflow for large primes
+    while divisor <= value / divisor {
+        let mut count = 0;
+        while value % divisor == 0 {
+            value /= divisor;
+            count += 1;
+        }
+        if count > 0 {
+            factors.push((divisor, count));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if value > 1 {
+        factors.push((value, 1));
+    }
+    factors
+}
+
+/// Make a table of the smallest prime factor of every value less than the limit,
+/// with zero for both zero and one.
+///
+/// This lets many values be factorized quickly using `factorize_with_spf`.
+pub fn smallest_prime_factor_sieve(limit: u64) -> Vec<u32> {
+    assert!(limit <= (u32::max_value() as u64));
+    let timer = DebugTimer::start();
+    let limit = limit as usize;
+    let mut spf = vec![0u32; limit];
+    for i in 2..limit {
+        if spf[i] == 0 {
+            // Nothing smaller divides it, so it must be prime
+            let mut j = i;
+            while j < limit {
+                if spf[j] == 0 {
+                    spf[j] = i as u32;
+                }
+                j += i;
+            }
+        }
+    }
+    timer.finish_with(|| format!("Computed smallest prime factors until {}", limit));
+    spf
+}
+
+/// Factorize the value using a table from `smallest_prime_factor_sieve`,
+/// giving the same result as `factorize`.
+pub fn factorize_with_spf(mut n: u64, spf: &[u32]) -> Vec<(u64, u32)> {
+    assert_ne!(n, 0);
+    assert!(n < (spf.len() as u64), "{} is beyond the limit {}", n, spf.len());
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    while n > 1 {
+        let prime = spf[n as usize] as u64;
+        match factors.last_mut() {
+            Some(&mut (last, ref mut count)) if last == prime => *count += 1,
+            _ => factors.push((prime, 1))
+        }
+        n /= prime;
+    }
+    factors
+}
+
+lazy_static! {
+    /// The primes that `factorize_fast` strips by trial division before using pollard's rho
+    static ref SMALL_PRIMES: Vec<u64> = primes(1000);
+}
+
+/// Factorize the value into its prime factors and their multiplicities,
+/// ordered from smallest to largest.
+///
+/// This strips small primes with trial division,
+/// then splits whatever remains using `pollard_rho`.
+pub fn factorize_fast(mut value: u64) -> Vec<(u64, u32)> {
+    assert_ne!(value, 0);
+    let mut factors = Vec::new();
+    for &prime in SMALL_PRIMES.iter() {
+        if prime * prime > value { break }
+        while value % prime == 0 {
+            value /= prime;
+            factors.push(prime);
+        }
+    }
+    let mut remaining = vec![value];
+    while let Some(value) = remaining.pop() {
+        if value == 1 { continue }
+        let factor = pollard_rho(value);
+        if factor == value {
+            factors.push(value);
+        } else {
+            remaining.push(factor);
+            remaining.push(value / factor);
+        }
+    }
+    factors.sort();
+    factors.into_iter()
+        .group_by(|&prime| prime).into_iter()
+        .map(|(prime, group)| (prime, group.count() as u32))
+        .collect()
+}
+
+/// Find a nontrivial factor of the value using pollard's rho algorithm,
+/// returning the value itself if it's prime.
+///
+/// This uses Brent's improved cycle detection,
+/// which batches the gcd computations together.
+pub fn pollard_rho(n: u64) -> u64 {
+    use num::integer::gcd;
+    use utils::mul_mod;
+    assert!(n > 1, "Invalid value: {}", n);
+    if n % 2 == 0 { return 2 }
+    if is_prime(n) { return n }
+    const BATCH_SIZE: u64 = 128;
+    let distance = |x: u64, y: u64| if x > y { x - y } else { y - x };
+    // If we get unlucky and find the trivial factor, retry with a different polynomial
+    for c in 1.. {
+        let step = |x: u64| (mul_mod(x, x, n) + c) % n;
+        let (mut x, mut y, mut saved) = (0, 2, 2);
+        let (mut factor, mut product, mut length) = (1, 1, 1);
+        while factor == 1 {
+            x = y;
+            for _ in 0..length {
+                y = step(y);
+            }
+            let mut k = 0;
+            while k < length && factor == 1 {
+                saved = y;
+                for _ in 0..BATCH_SIZE.min(length - k) {
+                    y = step(y);
+                    product = mul_mod(product, distance(x, y), n);
+                }
+                factor = gcd(product, n);
+                k += BATCH_SIZE;
+            }
+            length *= 2;
+        }
+        if factor == n {
+            // The batch overshot, so backtrack one step at a time
+            loop {
+                saved = step(saved);
+                factor = gcd(distance(x, saved), n);
+                if factor > 1 { break }
+            }
+        }
+        if factor != n {
+            return factor
+        }
+    }
+    unreachable!()
+}
+
+/// Euler's totient function, the count of values up to `n` that are coprime to it
+pub fn totient(n: u64) -> u64 {
+    factorize(n).iter().fold(n, |result, &(prime, _)| result / prime * (prime - 1))
+}
+
+/// The value below the limit that maximizes `n / totient(n)`.
+///
+/// Since the ratio is the product of `p / (p - 1)` over the distinct prime factors,
+/// this is always the largest primorial below the limit.
+pub fn max_totient_ratio_below(limit: u64) -> u64 {
+    assert!(limit > 1, "Invalid limit: {}", limit);
+    let mut result = 1u64;
+    let mut prime = 2;
+    while let Some(next) = result.checked_mul(prime) {
+        if next >= limit { break }
+        result = next;
+        prime = next_prime(prime);
+    }
+    result
+}
+
+/// Every right-truncatable prime, which stays prime as digits are removed from the right.
+///
+/// These are built digit by digit, starting from the single digit primes
+/// and appending each digit to the primes we've already found.
+/// There are only finitely many, so this always terminates.
+pub fn grow_right_truncatable_primes() -> Vec<u64> {
+    let mut result = Vec::new();
+    let mut current = vec![2, 3, 5, 7];
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &prime in &current {
+            for digit in 0..10 {
+                let candidate = prime * 10 + digit;
+                if is_prime(candidate) {
+                    next.push(candidate);
+                }
+            }
+        }
+        result.extend(current);
+        current = next;
+    }
+    result.sort();
+    result
+}
+
 /// The witnessess needed to make the miller-rabbin test deterministic
 fn needed_witnesses(value: u64) -> &'static [u32] {
     /*
@@ -102,6 +612,76 @@ fn needed_witnesses(value: u64) -> &'static [u32] {
     }
 }
 
+/// Grow a bitset from `prime_set` so it includes all primes less than the new limit.
+///
+/// Only the new part of the set needs to be sieved,
+/// as long as the existing set already contains the base primes up to the square root.
+pub fn grow_prime_set(set: &mut FixedBitSet, limit: u64) {
+    assert!(limit <= (usize::max_value() as u64));
+    let old_limit = set.len();
+    let limit = limit as usize;
+    if limit <= old_limit { return }
+    let root = isqrt(limit as u64) as usize + 1;
+    if old_limit <= root.max(2) {
+        // We don't know enough base primes, so we have to start over
+        *set = prime_set(limit as u64);
+        return
+    }
+    let timer = DebugTimer::start();
+    let base_primes = set.ones()
+        .take_while(|&p| p * p < limit)
+        .collect::<Vec<_>>();
+    set.grow(limit);
+    set.set_range(old_limit.., true);
+    for prime in base_primes {
+        let mut j = (prime * prime).max((old_limit + prime - 1) / prime * prime);
+        while j < limit {
+            set.set(j, false);
+            j += prime;
+        }
+    }
+    timer.finish_with(|| format!("Grew prime set from {} to {}", old_limit, limit));
+}
+
+/// A set of primes that grows as needed, re-sieving only the newly added range.
+///
+/// This is the replacement for the deprecated `IncrementalPrimeSet`.
+pub struct GrowablePrimeSet {
+    set: FixedBitSet
+}
+impl GrowablePrimeSet {
+    #[inline]
+    pub fn new() -> GrowablePrimeSet {
+        GrowablePrimeSet { set: FixedBitSet::default() }
+    }
+    #[inline]
+    pub fn with_initial_limit(limit: u64) -> GrowablePrimeSet {
+        GrowablePrimeSet { set: prime_set(limit) }
+    }
+    #[inline]
+    pub fn expand(&mut self, limit: u64) {
+        grow_prime_set(&mut self.set, limit)
+    }
+    #[inline]
+    pub fn limit(&self) -> u64 {
+        self.set.len() as u64
+    }
+    #[inline]
+    pub fn contains(&self, prime: u64) -> bool {
+        assert!(prime < self.limit(), "{} is beyond the limit {}", prime, self.limit());
+        self.set.contains(prime as usize)
+    }
+    /// Check if the specified value is prime,
+    /// growing the set if needed
+    pub fn check_prime(&mut self, value: u64) -> bool {
+        if value >= self.limit() {
+            let old_limit = self.limit();
+            self.expand((value + 1000).max(old_limit * 2));
+        }
+        self.contains(value)
+    }
+}
+
 const BFSZ: u64 = 1 << 16;
 const BFBTS: u64 = BFSZ * 32;
 const BFRNG: u64 = BFBTS * 2;
@@ -319,6 +899,28 @@ impl Iterator for IncrementalSieve {
 mod test {
     use super::*;
     #[test]
+    fn test_prime_set_range() {
+        let expected = primes(1000);
+        for &(start, end) in &[(0, 1000), (1, 2), (2, 3), (100, 200), (997, 1000), (500, 500)] {
+            let range = prime_set_range(start, end);
+            assert_eq!(range.len() as u64, end - start);
+            assert_eq!(
+                range.ones().map(|i| start + i as u64).collect_vec(),
+                expected.iter().cloned().filter(|&p| start <= p && p < end).collect_vec(),
+                "Range {}..{}", start, end
+            );
+        }
+        let large = 1_000_000_000_000u64;
+        let range = prime_set_range(large, large + 1000);
+        assert!(range.ones().all(|i| is_prime(large + i as u64)));
+        assert_eq!(range.count_ones(..), (large..(large + 1000)).filter(|&n| is_prime(n)).count());
+    }
+    #[test]
+    fn sieve_consistency() {
+        sieve_consistency_report(10).unwrap();
+        sieve_consistency_report(100_000).unwrap();
+    }
+    #[test]
     #[allow(deprecated)]
     fn test_incremental() {
         ::env_logger::init();
@@ -328,4 +930,205 @@ mod test {
             generate_primes_until(n);
         assert_eq!(incremental_primes, primes);
     }
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_sieve_matches_serial() {
+        let limit = 10_000_000;
+        let serial = serial_prime_set(limit);
+        let parallel = parallel_prime_set(limit);
+        assert_eq!(serial.len(), parallel.len());
+        assert_eq!(serial.as_slice(), parallel.as_slice());
+    }
+    #[test]
+    fn test_odd_prime_set() {
+        let limit = 1_000_000;
+        let primes = prime_set(limit);
+        let odd_primes = odd_prime_set(limit);
+        assert_eq!(odd_primes.len(), (limit / 2) as usize);
+        for n in 1..limit {
+            assert_eq!(is_set(&odd_primes, n), primes.contains(n as usize), "Disagreement on {}", n);
+        }
+    }
+    #[test]
+    fn small_odd_prime_sets() {
+        use std::panic::catch_unwind;
+        for limit in 0..4 {
+            let odd_primes = odd_prime_set(limit);
+            for n in 0..limit {
+                assert_eq!(is_set(&odd_primes, n), n == 2, "Wrong result for {} below {}", n, limit);
+            }
+            // Everything past the set's range panics the same way, whether it's even or odd
+            for &n in &[limit + 2, limit + 3, 1_000_000, 1_000_001] {
+                assert!(catch_unwind(|| is_set(&odd_primes, n)).is_err(), "Checked {} below {}", n, limit);
+            }
+        }
+        assert!(catch_unwind(|| is_set(&odd_prime_set(0), 1)).is_err());
+        assert!(catch_unwind(|| is_set(&odd_prime_set(0), 2)).is_err());
+        assert!(catch_unwind(|| is_set(&odd_prime_set(10), 1_000_001)).is_err());
+        assert!(catch_unwind(|| is_set(&odd_prime_set(10), 1_000_000)).is_err());
+    }
+    #[test]
+    fn test_prime_gaps() {
+        assert_eq!(
+            prime_gaps(20).collect::<Vec<_>>(),
+            vec![(2, 1), (3, 2), (5, 2), (7, 4), (11, 2), (13, 4), (17, 2)]
+        );
+        assert_eq!(record_prime_gaps(100), vec![(2, 1), (3, 2), (7, 4), (23, 6), (89, 8)]);
+        for limit in 0..4 {
+            assert!(record_prime_gaps(limit).is_empty(), "Found gaps below {}", limit);
+            assert_eq!(prime_gaps(limit).count(), 0);
+        }
+        assert!(primes(0).is_empty());
+        assert!(primes(2).is_empty());
+        assert_eq!(primes(3), vec![2]);
+    }
+    #[test]
+    fn test_consecutive_prime_sum_runs() {
+        let runs = consecutive_prime_sum_runs(100, 2);
+        // 2 + 3 + 5 + 7 + 11 + 13
+        assert!(runs.contains(&(41, 6)));
+        // 11 + 13 + 17
+        assert!(runs.contains(&(41, 3)));
+        assert!(runs.iter().all(|&(prime, length)| prime < 100 && length >= 2 && is_prime(prime)));
+        assert_eq!(runs.iter().max_by_key(|&&(_, length)| length), Some(&(41, 6)));
+        assert!(consecutive_prime_sum_runs(0, 2).is_empty());
+        assert!(consecutive_prime_sum_runs(1, 1).is_empty());
+        assert_eq!(consecutive_prime_sum_runs(6, 1), vec![(2, 1), (3, 1), (5, 1), (5, 2)]);
+    }
+    #[test]
+    fn test_squarefree() {
+        let expected = (1..20)
+            .filter(|&i| i % 4 != 0 && i % 9 != 0 && i % 16 != 0)
+            .collect::<Vec<_>>();
+        assert_eq!(squarefree_sieve(20).ones().collect::<Vec<_>>(), expected);
+        assert_eq!(count_squarefree(20), expected.len());
+        assert_eq!(count_squarefree(0), 0);
+        assert_eq!(count_squarefree(2), 1);
+    }
+    #[test]
+    fn test_max_totient_ratio() {
+        assert_eq!(max_totient_ratio_below(11), 6);
+        assert_eq!(max_totient_ratio_below(31), 30);
+        assert_eq!(max_totient_ratio_below(1_000_000), 510_510);
+        let ratio = |n: u64| n as f64 / totient(n) as f64;
+        // Multiples like 420 and 840 can tie, but never beat the primorial
+        let best = ratio(max_totient_ratio_below(1000));
+        assert!((2..1000).all(|n| ratio(n) <= best + 1e-9));
+    }
+    #[test]
+    fn test_right_truncatable_primes() {
+        let truncatable = grow_right_truncatable_primes();
+        assert_eq!(truncatable.len(), 83);
+        assert_eq!(&truncatable[..6], &[2, 3, 5, 7, 23, 29]);
+        assert_eq!(truncatable.last(), Some(&73_939_133));
+        for &prime in &truncatable {
+            let mut remaining = prime;
+            while remaining > 0 {
+                assert!(is_prime(remaining), "{} isn't prime, truncated from {}", remaining, prime);
+                remaining /= 10;
+            }
+        }
+    }
+    #[test]
+    fn test_large_is_prime() {
+        // The smallest prime above 2^40
+        assert!(is_prime(1_099_511_627_791));
+        assert!(!is_prime(1_099_511_627_793));
+        assert!(is_prime(10_000_000_019));
+        assert!(!is_prime(1_000_000_007 * 1_000_000_009));
+        // The largest prime below 2^64
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(18_446_744_073_709_551_559));
+    }
+    #[test]
+    fn test_next_prime() {
+        assert_eq!(next_prime(0), 2);
+        assert_eq!(next_prime(1), 2);
+        assert_eq!(next_prime(2), 3);
+        assert_eq!(next_prime(3), 5);
+        assert_eq!(next_prime(100), 101);
+        assert_eq!(next_prime(113), 127);
+        assert_eq!(prev_prime(0), None);
+        assert_eq!(prev_prime(2), None);
+        assert_eq!(prev_prime(3), Some(2));
+        assert_eq!(prev_prime(4), Some(3));
+        assert_eq!(prev_prime(5), Some(3));
+        assert_eq!(prev_prime(100), Some(97));
+        assert_eq!(prev_prime(127), Some(113));
+    }
+    #[test]
+    fn test_smallest_prime_factors() {
+        let spf = smallest_prime_factor_sieve(10_000);
+        assert_eq!(spf[0], 0);
+        assert_eq!(spf[1], 0);
+        assert_eq!(spf[2], 2);
+        assert_eq!(spf[15], 3);
+        assert_eq!(spf[49], 7);
+        assert_eq!(spf[9973], 9973);
+        for value in 1..10_000 {
+            assert_eq!(factorize_with_spf(value, &spf), factorize(value), "Disagreement on {}", value);
+        }
+    }
+    #[test]
+    fn test_pollard_rho() {
+        let (p, q) = (10_007, 65_519);
+        let factor = pollard_rho(p * q);
+        assert!(factor == p || factor == q, "Invalid factor: {}", factor);
+        assert_eq!(pollard_rho(2 * 99991), 2);
+        assert_eq!(pollard_rho(99991), 99991);
+        assert_eq!(factorize_fast(p * q), vec![(p, 1), (q, 1)]);
+        assert_eq!(factorize_fast(1), vec![]);
+        for value in 1..10_000 {
+            assert_eq!(factorize_fast(value), factorize(value), "Disagreement on {}", value);
+        }
+    }
+    #[test]
+    fn pollard_rho_large_semiprime() {
+        let (p, q) = (1_000_000_007, 1_000_000_009);
+        assert_eq!(factorize_fast(p * q), vec![(p, 1), (q, 1)]);
+        let large = 999_983u64 * 999_983 * 1_000_003;
+        assert_eq!(factorize_fast(large), vec![(999_983, 2), (1_000_003, 1)]);
+    }
+    #[test]
+    fn test_growable() {
+        let mut set = GrowablePrimeSet::with_initial_limit(100);
+        for value in 0..200_000 {
+            assert_eq!(set.check_prime(value), is_prime(value), "Disagreement on {}", value);
+        }
+        assert!(set.limit() >= 200_000);
+        let mut set = GrowablePrimeSet::new();
+        assert!(set.check_prime(7919));
+        assert!(!set.check_prime(7921));
+    }
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(2), vec![(2, 1)]);
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(9_999_991), vec![(9_999_991, 1)]);
+        assert_eq!(totient(1), 1);
+        assert_eq!(totient(36), 12);
+        assert_eq!(totient(97), 96);
+        assert_eq!(
+            factorize(u64::max_value()),
+            vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6_700_417, 1)]
+        );
+        assert_eq!(totient(13_963_646_602_082_100_175), 9_575_071_955_713_440_120);
+    }
+    #[test]
+    fn test_twin_primes() {
+        assert_eq!(
+            twin_primes(100).take(4).collect::<Vec<_>>(),
+            vec![(3, 5), (5, 7), (11, 13), (17, 19)]
+        );
+        assert_eq!(twin_primes(7).collect::<Vec<_>>(), vec![(3, 5)]);
+        for limit in 0..5 {
+            assert_eq!(twin_primes(limit).count(), 0, "Found twin primes below {}", limit);
+        }
+        assert_eq!(twin_primes(6).collect::<Vec<_>>(), vec![(3, 5)]);
+        assert_eq!(twin_primes(1000).count(), 35);
+        assert_eq!(twin_primes(1000).last(), Some((881, 883)));
+        let set = prime_set(10_000);
+        assert!(IntoOnes::new(set.clone()).eq(set.ones()));
+    }
 }
@@ -0,0 +1,131 @@
+//! Parsing and formatting roman numerals
+use failure::Error;
+
+/// The largest value that can be written without a bar over the numerals
+pub const MAX_ROMAN: u32 = 3999;
+
+/// The numerals and subtractive pairs, from largest to smallest
+const NUMERALS: &[(&str, u32)] = &[
+    ("M", 1000), ("CM", 900), ("D", 500), ("CD", 400),
+    ("C", 100), ("XC", 90), ("L", 50), ("XL", 40),
+    ("X", 10), ("IX", 9), ("V", 5), ("IV", 4), ("I", 1)
+];
+
+#[inline]
+fn numeral_value(c: char) -> Option<u32> {
+    Some(match c {
+        'I' => 1,
+        'V' => 5,
+        'X' => 10,
+        'L' => 50,
+        'C' => 100,
+        'D' => 500,
+        'M' => 1000,
+        _ => return None
+    })
+}
+
+/// Parse the roman numerals, which don't have to be in their minimal form (like `IIII`).
+///
+/// Subtractive pairs are limited to `I` before `V` or `X`, `X` before `L` or `C`,
+/// and `C` before `D` or `M`. Once the pairs are combined the numerals can't increase,
+/// and a pair can't follow (or be followed by) a numeral that's no larger than the one it subtracts.
+/// Since they'd be redundant, `V`, `L` and `D` can only appear once.
+pub fn parse_roman(text: &str) -> Result<u32, Error> {
+    let values = text.chars()
+        .map(|c| numeral_value(c).ok_or_else(|| format_err!("Invalid numeral {:?} in {:?}", c, text)))
+        .collect::<Result<Vec<u32>, Error>>()?;
+    for &single in &[5, 50, 500] {
+        ensure!(
+            values.iter().filter(|&&value| value == single).count() <= 1,
+            "Repeated numeral in {:?}", text
+        );
+    }
+    // The value of each group of numerals, its smallest numeral, and whether it's a subtractive pair
+    let mut groups: Vec<(u32, u32, bool)> = Vec::with_capacity(values.len());
+    let mut index = 0;
+    while index < values.len() {
+        let value = values[index];
+        match values.get(index + 1) {
+            Some(&next) if next > value => {
+                ensure!(
+                    (value == 1 || value == 10 || value == 100) && next <= value * 10,
+                    "Invalid subtractive pair in {:?}", text
+                );
+                groups.push((next - value, value, true));
+                index += 2;
+            },
+            _ => {
+                groups.push((value, value, false));
+                index += 1;
+            }
+        }
+    }
+    for pair in groups.windows(2) {
+        let (previous, previous_smallest, previous_subtractive) = pair[0];
+        let (value, smallest, subtractive) = pair[1];
+        ensure!(value <= previous, "Numerals increase in {:?}", text);
+        ensure!(
+            !(previous_subtractive || subtractive) || smallest < previous_smallest,
+            "Misplaced subtractive pair in {:?}", text
+        );
+    }
+    let total = groups.iter().map(|&(value, _, _)| value).sum::<u32>();
+    ensure!(total > 0, "Invalid roman numerals {:?}", text);
+    ensure!(total <= MAX_ROMAN, "Roman numerals {:?} are too large", text);
+    Ok(total)
+}
+
+/// Write the value in its minimal roman numeral form, using subtractive pairs where possible
+pub fn to_roman(mut value: u32) -> Result<String, Error> {
+    ensure!(value > 0 && value <= MAX_ROMAN, "Can't write {} in roman numerals", value);
+    let mut result = String::new();
+    for &(numerals, amount) in NUMERALS {
+        while value >= amount {
+            result.push_str(numerals);
+            value -= amount;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn parse() {
+        assert_eq!(parse_roman("MCMXCIV").unwrap(), 1994);
+        assert_eq!(parse_roman("XLIX").unwrap(), 49);
+        assert_eq!(parse_roman("IV").unwrap(), 4);
+        assert_eq!(parse_roman("IX").unwrap(), 9);
+        assert_eq!(parse_roman("MMMCMXCIX").unwrap(), 3999);
+        // Numerals don't have to be minimal
+        assert_eq!(parse_roman("XIIIIII").unwrap(), 16);
+        assert_eq!(parse_roman("XVI").unwrap(), 16);
+        assert!(parse_roman("").is_err());
+        assert!(parse_roman("IC").is_err());
+        assert!(parse_roman("VX").is_err());
+        for &invalid in &["IIV", "IXX", "VV", "IIX", "XCX", "CMD", "IVI", "VIV", "LL", "DCD"] {
+            assert!(parse_roman(invalid).is_err(), "Parsed {:?}", invalid);
+        }
+        assert_eq!(parse_roman("CDXC").unwrap(), 490);
+        assert_eq!(parse_roman("XIX").unwrap(), 19);
+        assert!(parse_roman("MMMM").is_err());
+        assert!(parse_roman("XIZ").is_err());
+    }
+    #[test]
+    fn format() {
+        assert_eq!(to_roman(1994).unwrap(), "MCMXCIV");
+        assert_eq!(to_roman(16).unwrap(), "XVI");
+        assert_eq!(to_roman(3999).unwrap(), "MMMCMXCIX");
+        assert!(to_roman(0).is_err());
+        assert!(to_roman(4000).is_err());
+    }
+    #[test]
+    fn round_trip() {
+        for value in 1..=MAX_ROMAN {
+            let numerals = to_roman(value).unwrap();
+            assert_eq!(parse_roman(&numerals).unwrap(), value, "Invalid round trip for {}", numerals);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Helpers for the word-based problems
+use std::collections::HashMap;
+
+/// Find all pairs of words that are anagrams of each other.
+///
+/// Words are grouped by their sorted letters,
+/// and the pairs are given in the order the words first appear.
+pub fn find_anagram_pairs(words: &[String]) -> Vec<(String, String)> {
+    let mut groups: Vec<Vec<&String>> = Vec::new();
+    let mut group_indexes = HashMap::new();
+    for word in words {
+        let mut signature = word.chars().collect::<Vec<char>>();
+        signature.sort();
+        let index = *group_indexes.entry(signature).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(word);
+    }
+    let mut pairs = Vec::new();
+    for group in &groups {
+        for (index, &first) in group.iter().enumerate() {
+            for &second in &group[index + 1..] {
+                pairs.push((first.clone(), second.clone()));
+            }
+        }
+    }
+    pairs
+}
+
+/// The sum of the alphabetical positions of the word's letters, so `A` is 1 and `Z` is 26.
+///
+/// Case is ignored, and anything that isn't an ASCII letter (like quotes) is skipped.
+pub fn word_value(word: &str) -> u64 {
+    word.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| (b.to_ascii_uppercase() - b'A' + 1) as u64)
+        .sum()
+}
+
+/// Count the words whose `word_value` is a figurate number according to the predicate,
+/// like the triangle numbers for the coded triangle words problem.
+pub fn count_figurate_words<F: Fn(u64) -> bool>(words: &[String], is_figurate: F) -> usize {
+    words.iter().filter(|word| is_figurate(word_value(word))).count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|&word| String::from(word)).collect()
+    }
+    #[test]
+    fn anagram_pairs() {
+        let pairs = find_anagram_pairs(&words(&["CARE", "RACE", "ACRE", "BEAR"]));
+        assert_eq!(pairs, vec![
+            (String::from("CARE"), String::from("RACE")),
+            (String::from("CARE"), String::from("ACRE")),
+            (String::from("RACE"), String::from("ACRE")),
+        ]);
+        assert_eq!(find_anagram_pairs(&words(&["BEAR", "BARE"])).len(), 1);
+        assert!(find_anagram_pairs(&words(&["BEAR", "BEARS"])).is_empty());
+    }
+    #[test]
+    fn figurate_words() {
+        assert_eq!(word_value("SKY"), 55);
+        assert_eq!(word_value("\"sky\""), 55);
+        let is_triangular = |value: u64| {
+            (1..).map(|n| n * (n + 1) / 2)
+                .take_while(|&triangle| triangle <= value)
+                .any(|triangle| triangle == value)
+        };
+        let is_square = |value: u64| (1..).map(|n| n * n)
+            .take_while(|&square| square <= value)
+            .any(|square| square == value);
+        // SKY is 55, A is 1, AB is 3, and ZZ is 52
+        let list = words(&["SKY", "A", "AB", "ZZ"]);
+        assert_eq!(count_figurate_words(&list, is_triangular), 3);
+        assert_eq!(count_figurate_words(&list, is_square), 1);
+    }
+}
@@ -0,0 +1,81 @@
+//! Writing numbers out in english words
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen",
+    "sixteen", "seventeen", "eighteen", "nineteen"
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"
+];
+
+/// Write the number in british english, like `"three hundred and forty-two"`.
+///
+/// Following the Project Euler convention, "and" always separates the hundreds
+/// from the rest of the number. This panics for numbers of a million or more.
+pub fn number_to_words(n: u64) -> String {
+    assert!(n < 1_000_000, "Unable to write {} in words", n);
+    let mut result = String::new();
+    if n < 1000 {
+        write_below_thousand(&mut result, n);
+    } else {
+        write_below_thousand(&mut result, n / 1000);
+        result.push_str(" thousand");
+        let remainder = n % 1000;
+        if remainder > 0 {
+            result.push_str(if remainder < 100 { " and " } else { " " });
+            write_below_thousand(&mut result, remainder);
+        }
+    }
+    result
+}
+
+fn write_below_thousand(out: &mut String, n: u64) {
+    debug_assert!(n < 1000);
+    let (hundreds, remainder) = (n / 100, n % 100);
+    if hundreds > 0 {
+        out.push_str(ONES[hundreds as usize]);
+        out.push_str(" hundred");
+        if remainder == 0 { return }
+        out.push_str(" and ");
+    }
+    if remainder < 20 {
+        out.push_str(ONES[remainder as usize]);
+    } else {
+        out.push_str(TENS[(remainder / 10) as usize]);
+        if remainder % 10 > 0 {
+            out.push('-');
+            out.push_str(ONES[(remainder % 10) as usize]);
+        }
+    }
+}
+
+/// The number of letters needed to write the number in words, ignoring spaces and hyphens
+pub fn letter_count(n: u64) -> usize {
+    number_to_words(n).chars()
+        .filter(|c| c.is_alphabetic())
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn words() {
+        assert_eq!(number_to_words(342), "three hundred and forty-two");
+        assert_eq!(number_to_words(115), "one hundred and fifteen");
+        assert_eq!(number_to_words(0), "zero");
+        assert_eq!(number_to_words(19), "nineteen");
+        assert_eq!(number_to_words(40), "forty");
+        assert_eq!(number_to_words(300), "three hundred");
+        assert_eq!(number_to_words(1000), "one thousand");
+        assert_eq!(number_to_words(1005), "one thousand and five");
+        assert_eq!(number_to_words(21_342), "twenty-one thousand three hundred and forty-two");
+    }
+    #[test]
+    fn letter_counts() {
+        assert_eq!(letter_count(342), 23);
+        assert_eq!(letter_count(115), 20);
+        assert_eq!((1..=5).map(letter_count).sum::<usize>(), 19);
+        assert_eq!((1..=1000).map(letter_count).sum::<usize>(), 21124);
+    }
+}
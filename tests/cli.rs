@@ -0,0 +1,89 @@
+use std::env;
+use std::process::{Command, Output};
+
+/// Run the `euler` binary, which cargo builds next to the integration tests
+fn euler(args: &[&str]) -> Output {
+    let mut path = env::current_exe().unwrap();
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push(format!("euler{}", env::consts::EXE_SUFFIX));
+    Command::new(&path).args(args).output()
+        .unwrap_or_else(|e| panic!("Unable to run {}: {}", path.display(), e))
+}
+
+#[test]
+fn list_problems() {
+    let output = euler(&["--list"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let names = stdout.lines().collect::<Vec<_>>();
+    assert!(names.contains(&"poker"), "Missing poker: {:?}", names);
+    assert!(names.contains(&"xor_decryption"), "Missing xor_decryption: {:?}", names);
+}
+
+#[test]
+fn unknown_problem_suggests_list() {
+    let output = euler(&["nonexistent"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--list"), "Unexpected error: {:?}", stderr);
+}
+
+#[test]
+fn timed_solve() {
+    let output = euler(&["--time", "poker"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Solved poker: 376 (in "), "Unexpected output: {:?}", stdout);
+    assert!(stdout.trim().ends_with(" ms)"), "Unexpected output: {:?}", stdout);
+}
+
+/// Extract the raw value of a top-level field from a flat json object
+fn json_field<'a>(json: &'a str, field: &str) -> Option<&'a str> {
+    let key = format!("\"{}\":", field);
+    let start = json.find(&key)? + key.len();
+    let rest = &json[start..];
+    let end = if rest.starts_with('"') {
+        rest[1..].find('"')? + 2
+    } else {
+        rest.find(|c| c == ',' || c == '}')?
+    };
+    Some(&rest[..end])
+}
+
+#[test]
+fn json_output() {
+    let output = euler(&["--format", "json", "poker"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json = stdout.trim();
+    assert!(json.starts_with('{') && json.ends_with('}'), "Invalid json: {:?}", json);
+    assert_eq!(json_field(json, "problem"), Some("\"poker\""));
+    assert_eq!(json_field(json, "result"), Some("\"376\""));
+    let elapsed = json_field(json, "elapsed_ms").unwrap().parse::<f64>().unwrap();
+    assert!(elapsed >= 0.0);
+    assert_eq!(json_field(json, "error"), None);
+
+    let output = euler(&["--format", "json", "nonexistent"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(json_field(stdout.trim(), "problem"), Some("\"nonexistent\""));
+    assert!(json_field(stdout.trim(), "error").is_some(), "Missing error: {:?}", stdout);
+}
+
+#[test]
+fn solve_in_parallel() {
+    let output = euler(&["poker", "spiral_primes"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines().collect::<Vec<_>>();
+    // The problems are printed in the order they finish
+    lines.sort();
+    assert_eq!(lines, vec!["Solved poker: 376", "Solved spiral_primes: 26241"]);
+    let output = euler(&["--format", "json", "poker", "nonexistent"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+}